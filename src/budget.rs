@@ -0,0 +1,436 @@
+//! 空间预算（`--max-total`、`--budgets`）：超限时列出违规项并以独立的退出码结束。
+
+use crate::cli::{CliError, CliResult};
+use crate::format::parse_size;
+use crate::i18n::{tr, trf};
+use crate::scan::DirStats;
+use crate::visit::{VisitEntry, Visitor};
+use crate::warnings::WarningTracker;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+// ---- 规则文件 ----
+//
+// 每行一条 `<目标> <= <大小> [each]`，行首或空白之后的 `#` 起为注释（路径中的 `#` 照常保留）：
+//   dist/ <= 50M                  路径（相对扫描根）的子树总大小
+//   assets/**/*.png <= 5M each    每个匹配的文件各自不超过上限
+//   *.log <= 100M                 所有匹配文件的合计
+// 含 `*`、`?` 的目标是通配模式，只匹配非目录条目；`**` 匹配任意层目录，
+// 不含 `/` 的模式与 `-e` 的名称规则一样在任意层级匹配。
+
+/// 预算目标
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum BudgetTarget {
+    /// 相对扫描根的路径（目录为子树总大小，文件为自身大小）
+    Path(PathBuf),
+    /// 通配模式（按 `/` 拆分的各段）；`each` 为逐个文件比较，否则比较合计
+    Glob { segments: Vec<String>, each: bool },
+}
+
+/// 规则文件中的一条预算
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct BudgetRule {
+    /// 原始文本，用于报告违规
+    pub(crate) text: String,
+    pub(crate) target: BudgetTarget,
+    pub(crate) limit: u64,
+}
+
+/// 全部预算；为空时不做任何检查，输出与未指定预算时完全一致
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Budgets {
+    pub(crate) max_total: Option<u64>,
+    pub(crate) rules: Vec<BudgetRule>,
+}
+
+impl Budgets {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.max_total.is_none() && self.rules.is_empty()
+    }
+
+    /// 读取规则文件，追加到 `rules`
+    pub(crate) fn load_file(&mut self, path: &Path) -> CliResult<()> {
+        let err = |msg: String| CliError::BudgetFile {
+            path: path.to_path_buf(),
+            msg,
+        };
+        let text = fs::read_to_string(path).map_err(|e| err(e.to_string()))?;
+        for (i, line) in text.lines().enumerate() {
+            let line = strip_comment(line).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let rule = parse_rule(line).map_err(|m| err(trf("第 {} 行: {}", &[&(i + 1), &m])))?;
+            self.rules.push(rule);
+        }
+        Ok(())
+    }
+}
+
+/// 去掉注释：只有位于行首或紧跟空白的 `#` 才开始注释
+fn strip_comment(line: &str) -> &str {
+    let mut prev_space = true;
+    for (i, c) in line.char_indices() {
+        if c == '#' && prev_space {
+            return &line[..i];
+        }
+        prev_space = c.is_whitespace();
+    }
+    line
+}
+
+fn parse_rule(line: &str) -> Result<BudgetRule, String> {
+    let (target, rest) = line
+        .split_once("<=")
+        .ok_or_else(|| tr("缺少 '<='（格式：<路径或模式> <= <大小> [each]）").to_string())?;
+    let target = target.trim();
+    let mut words = rest.split_whitespace();
+    let size = words.next().ok_or_else(|| tr("缺少大小").to_string())?;
+    let limit = parse_size(size).ok_or_else(|| trf("无效的大小 '{}'", &[&size]))?;
+    let each = match words.next() {
+        None => false,
+        Some("each") => true,
+        Some(w) => return Err(trf("无法识别 '{}'", &[&w])),
+    };
+    if let Some(w) = words.next() {
+        return Err(trf("无法识别 '{}'", &[&w]));
+    }
+
+    let target = target.strip_prefix("./").unwrap_or(target);
+    if target.is_empty() {
+        return Err(tr("缺少路径或模式").to_string());
+    }
+    let target = if target.contains(['*', '?']) {
+        let mut segments: Vec<String> = target
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect();
+        if !target.contains('/') {
+            segments.insert(0, "**".to_string());
+        }
+        BudgetTarget::Glob { segments, each }
+    } else if each {
+        return Err(tr("each 只能用于通配模式").to_string());
+    } else {
+        BudgetTarget::Path(PathBuf::from(target.trim_end_matches('/')))
+    };
+    Ok(BudgetRule {
+        text: line.to_string(),
+        target,
+        limit,
+    })
+}
+
+// ---- 通配匹配 ----
+
+/// 单段匹配：`*` 任意长度、`?` 单个字符
+fn segment_match(pat: &str, s: &str) -> bool {
+    let (p, s): (Vec<char>, Vec<char>) = (pat.chars().collect(), s.chars().collect());
+    // 经典回溯：记住最近一个 `*` 的位置，失配时让它多吞一个字符
+    let (mut pi, mut si) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while si < s.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == s[si]) {
+            pi += 1;
+            si += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, si));
+            pi += 1;
+        } else if let Some((sp, ss)) = star {
+            pi = sp + 1;
+            si = ss + 1;
+            star = Some((sp, ss + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+fn glob_match(pat: &[String], path: &[String]) -> bool {
+    match pat.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => {
+            (0..=path.len()).any(|i| glob_match(rest, &path[i..]))
+        }
+        Some((first, rest)) => path
+            .split_first()
+            .is_some_and(|(p, tail)| segment_match(first, p) && glob_match(rest, tail)),
+    }
+}
+
+// ---- 检查 ----
+
+/// 一项超出预算的结果
+#[derive(Debug, Clone)]
+pub(crate) struct Violation {
+    /// 触发的规则原文；`--max-total` 记为参数名
+    pub(crate) rule: String,
+    /// 超限的路径；合计类规则为扫描根
+    pub(crate) path: PathBuf,
+    pub(crate) size: u64,
+    pub(crate) limit: u64,
+}
+
+/// 收集规则文件所需的逐项统计；挂在主扫描的 `ScanHooks.visitor` 上，不另做遍历
+pub(crate) struct BudgetVisitor<'a> {
+    root: &'a Path,
+    rules: &'a [BudgetRule],
+    /// 路径规则的 (规则序号, 绝对路径)，构造时拼接一次
+    paths: Vec<(usize, PathBuf)>,
+    /// 没有通配规则时无需为每个条目拆分路径
+    has_glob: bool,
+    /// 每条规则的累计大小（路径规则为该路径的大小）
+    sizes: Vec<AtomicU64>,
+    /// 路径规则的目标是否出现在遍历中
+    seen: Vec<AtomicBool>,
+    /// each 规则的逐个违规（规则序号, 违规）
+    each_hits: Mutex<Vec<(usize, Violation)>>,
+}
+
+impl<'a> BudgetVisitor<'a> {
+    pub(crate) fn new(root: &'a Path, rules: &'a [BudgetRule]) -> Self {
+        let paths = rules
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| match &r.target {
+                BudgetTarget::Path(p) => Some((i, root.join(p))),
+                BudgetTarget::Glob { .. } => None,
+            })
+            .collect();
+        let has_glob = rules
+            .iter()
+            .any(|r| matches!(r.target, BudgetTarget::Glob { .. }));
+        Self {
+            root,
+            rules,
+            paths,
+            has_glob,
+            sizes: rules.iter().map(|_| AtomicU64::new(0)).collect(),
+            seen: rules.iter().map(|_| AtomicBool::new(false)).collect(),
+            each_hits: Mutex::default(),
+        }
+    }
+
+    fn record_path(&self, path: &Path, size: u64) {
+        for (i, p) in &self.paths {
+            if p == path {
+                self.sizes[*i].store(size, Ordering::Relaxed);
+                self.seen[*i].store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+impl Visitor for BudgetVisitor<'_> {
+    fn entry(&self, e: &VisitEntry<'_>) {
+        self.record_path(e.path, e.size);
+        if !self.has_glob {
+            return;
+        }
+        let Ok(rel) = e.path.strip_prefix(self.root) else {
+            return;
+        };
+        let segs: Vec<String> = rel
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => Some(s.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        for (i, rule) in self.rules.iter().enumerate() {
+            let BudgetTarget::Glob { segments, each } = &rule.target else {
+                continue;
+            };
+            if !glob_match(segments, &segs) {
+                continue;
+            }
+            if !*each {
+                self.sizes[i].fetch_add(e.size, Ordering::Relaxed);
+            } else if e.size > rule.limit {
+                let v = Violation {
+                    rule: rule.text.clone(),
+                    path: e.path.to_path_buf(),
+                    size: e.size,
+                    limit: rule.limit,
+                };
+                self.each_hits
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push((i, v));
+            }
+        }
+    }
+
+    fn exit_dir(&self, path: &Path, totals: DirStats) {
+        self.record_path(path, totals.size);
+    }
+}
+
+/// 检查全部预算。`total` 为本次扫描得到的总大小（用于 `--max-total`）；
+/// 规则文件的逐项统计由挂在同一次扫描上的 `visitor` 收集（没有规则时为 None）。
+/// 违规按规则顺序排列，同一 each 规则内按路径排序。
+pub(crate) fn check_budgets(
+    root: &Path,
+    total: u64,
+    budgets: &Budgets,
+    visitor: Option<BudgetVisitor>,
+    warnings: &WarningTracker,
+) -> Vec<Violation> {
+    let mut out = Vec::new();
+    if let Some(limit) = budgets.max_total
+        && total > limit
+    {
+        out.push(Violation {
+            rule: "--max-total".to_string(),
+            path: root.to_path_buf(),
+            size: total,
+            limit,
+        });
+    }
+    let Some(visitor) = visitor else {
+        return out;
+    };
+
+    let rules = visitor.rules;
+    let mut each_hits = visitor
+        .each_hits
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner());
+    each_hits.sort_by(|a, b| a.1.path.cmp(&b.1.path));
+    for (i, rule) in rules.iter().enumerate() {
+        let size = visitor.sizes[i].load(Ordering::Relaxed);
+        match &rule.target {
+            BudgetTarget::Path(p) => {
+                if !visitor.seen[i].load(Ordering::Relaxed) {
+                    warnings.warn_msg(&trf("预算规则的路径不存在或已被排除: {}", &[&p.display()]));
+                } else if size > rule.limit {
+                    out.push(Violation {
+                        rule: rule.text.clone(),
+                        path: root.join(p),
+                        size,
+                        limit: rule.limit,
+                    });
+                }
+            }
+            BudgetTarget::Glob { each: true, .. } => {
+                out.extend(
+                    each_hits
+                        .iter()
+                        .filter(|(r, _)| *r == i)
+                        .map(|(_, v)| v.clone()),
+                );
+            }
+            BudgetTarget::Glob { each: false, .. } => {
+                if size > rule.limit {
+                    out.push(Violation {
+                        rule: rule.text.clone(),
+                        path: root.to_path_buf(),
+                        size,
+                        limit: rule.limit,
+                    });
+                }
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{ScanHooks, analyze_directory};
+    use crate::test_util::Fixture;
+
+    #[test]
+    fn test_budget_rules_parse_and_match() {
+        assert_eq!(
+            parse_rule("./dist/ <= 50M").expect("path rule").target,
+            BudgetTarget::Path(PathBuf::from("dist"))
+        );
+        let png = parse_rule("assets/**/*.png <= 5K each").expect("glob rule");
+        assert_eq!(png.limit, 5 * 1024);
+        let BudgetTarget::Glob { segments, each } = &png.target else {
+            panic!("expected glob");
+        };
+        assert!(*each);
+        let path = |s: &str| s.split('/').map(str::to_string).collect::<Vec<_>>();
+        assert!(glob_match(segments, &path("assets/a.png")));
+        assert!(glob_match(segments, &path("assets/x/y/b.png")));
+        assert!(!glob_match(segments, &path("other/a.png")));
+        assert!(!glob_match(segments, &path("assets/a.jpg")));
+        assert!(segment_match("*.tar.*", "a.tar.gz"));
+        assert!(segment_match("f?o", "foo"));
+        assert!(!segment_match("f?o", "fo"));
+        assert!(parse_rule("dist <= 5X").is_err());
+        assert!(parse_rule("dist <= 5M each").is_err());
+        assert!(parse_rule("dist 5M").is_err());
+        // 只有行首或空白后的 # 开始注释
+        assert_eq!(strip_comment("# 注释"), "");
+        assert_eq!(strip_comment("dist/ <= 5M  # 发布包"), "dist/ <= 5M  ");
+        assert_eq!(
+            parse_rule(strip_comment("issue#12/ <= 1K"))
+                .expect("path with #")
+                .target,
+            BudgetTarget::Path(PathBuf::from("issue#12"))
+        );
+    }
+
+    #[test]
+    fn test_check_budgets() {
+        let fx = Fixture::new("yp_budgets");
+        let root = fx.root();
+        fx.file("dist/app.js", [0u8; 300]);
+        let big = fx.file("assets/img/big.png", [0u8; 200]);
+        fx.file("assets/small.png", [0u8; 50]);
+        let rules = fx.file(
+            "budgets.txt",
+            "# 预算\ndist/ <= 100\nassets/**/*.png <= 100 each\n*.png <= 1K\nmissing <= 1\n",
+        );
+
+        let mut budgets = Budgets {
+            max_total: Some(10),
+            ..Default::default()
+        };
+        budgets.load_file(&rules).expect("load rules");
+        assert_eq!(budgets.rules.len(), 4);
+
+        // 规则统计挂在主扫描（非 recursive）上，与显示的报告出自同一次遍历
+        let visitor = BudgetVisitor::new(root, &budgets.rules);
+        let report = analyze_directory(
+            root,
+            false,
+            &fx.excludes,
+            &fx.warnings,
+            ScanHooks {
+                visitor: Some(&visitor),
+                ..Default::default()
+            },
+        )
+        .expect("scan should succeed");
+        let total = report.total_size;
+        let v = check_budgets(root, total, &budgets, Some(visitor), &fx.warnings);
+        let got: Vec<_> = v.iter().map(|v| (v.path.clone(), v.size)).collect();
+        assert_eq!(
+            got,
+            [
+                (root.to_path_buf(), total),
+                (fx.path("dist"), 300),
+                (big, 200)
+            ]
+        );
+        // 不存在的路径只告警
+        assert_eq!(fx.warnings.take_collected().len(), 1);
+
+        let bad = fx.file("bad.txt", "dist <= lots\n");
+        let err = Budgets::default().load_file(&bad).unwrap_err();
+        // 语言是进程级设置，两种译文都接受
+        assert!(matches!(
+            err,
+            CliError::BudgetFile { ref msg, .. } if msg.starts_with("第 1 行") || msg.starts_with("line 1:")
+        ));
+    }
+}
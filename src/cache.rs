@@ -0,0 +1,313 @@
+//! 持久扫描缓存（按 dev/ino/mtime 复用未变化的目录）。
+
+use crate::exclude::{ExcludeSet, absify_for_compare};
+use crate::i18n::tr;
+use crate::scan::DirStats;
+use crate::warnings::{CTX_CACHE_READ, CTX_CACHE_WRITE, WarningTracker};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// ---- 持久扫描缓存 ----
+//
+// 按目录记录 (dev, ino, mtime)、本层统计（文件大小之和、文件数、子目录数）与子目录名。
+// 目录 mtime 未变说明其直接子项集合未变，直接沿用本层统计，跳过 read_dir 与逐文件 stat；
+// 子目录仍逐个 stat 校验并递归，深层的增删会被发现，子树总量由各层统计汇总。
+// 局限：原地改写文件（追加日志、数据库增长）不改变所在目录的 mtime，缓存命中时沿用旧大小，
+// 需要 --refresh 重建。
+// 仅非 recursive 的 report 模式与 tree 模式使用；recursive report 需要逐项条目，不走缓存。
+
+pub(crate) const SCAN_CACHE_VERSION: u32 = 3;
+
+/// 目录身份 + 修改时间，用于判断缓存是否仍然有效。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) struct DirStamp {
+    pub(crate) dev: u64,
+    pub(crate) ino: u64,
+    pub(crate) mtime_s: i64,
+    pub(crate) mtime_ns: u32,
+}
+
+impl DirStamp {
+    #[cfg(unix)]
+    pub(crate) fn from_meta(meta: &fs::Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+        Self {
+            dev: meta.dev(),
+            ino: meta.ino(),
+            mtime_s: meta.mtime(),
+            mtime_ns: meta.mtime_nsec() as u32,
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn from_meta(meta: &fs::Metadata) -> Self {
+        let d = meta
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        Self {
+            dev: 0,
+            ino: 0,
+            mtime_s: d.as_secs() as i64,
+            mtime_ns: d.subsec_nanos(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub(crate) struct CachedDir {
+    pub(crate) stamp: DirStamp,
+    /// 写入时排除规则的指纹；规则不同则不复用
+    pub(crate) excludes: u64,
+    /// 本层叶子统计（不含子目录）
+    pub(crate) level: DirStats,
+    /// 直接子目录名（已应用排除规则）
+    pub(crate) subdirs: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub(crate) struct ScanCacheFile {
+    pub(crate) version: u32,
+    pub(crate) dirs: HashMap<String, CachedDir>,
+}
+
+/// 缓存选项（`--cache` / `--no-cache` / `--refresh`，环境变量 `YP_CACHE=1`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct CacheOptions {
+    pub(crate) enabled: bool,
+    /// 忽略已有缓存，完整扫描后重写
+    pub(crate) refresh: bool,
+}
+
+#[derive(Debug)]
+pub(crate) struct ScanCache {
+    pub(crate) file: PathBuf,
+    /// 上次运行写入的记录（只读，供 worker 并发查询）
+    pub(crate) previous: HashMap<PathBuf, CachedDir>,
+    /// 本次运行确认/新建的记录
+    pub(crate) fresh: Mutex<HashMap<PathBuf, CachedDir>>,
+    pub(crate) excludes: u64,
+}
+
+pub(crate) fn env_path(key: &str) -> Option<PathBuf> {
+    std::env::var_os(key)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+}
+
+/// 缓存文件位置：YP_CACHE_FILE > $XDG_CACHE_HOME/yp > ~/.cache/yp > %LOCALAPPDATA%\\yp
+pub(crate) fn default_scan_cache_file() -> Option<PathBuf> {
+    if let Some(p) = env_path("YP_CACHE_FILE") {
+        return Some(p);
+    }
+    let base = env_path("XDG_CACHE_HOME")
+        .or_else(|| env_path("HOME").map(|h| h.join(".cache")))
+        .or_else(|| env_path("LOCALAPPDATA"))?;
+    Some(base.join("yp").join("scan-cache.json"))
+}
+
+pub(crate) fn open_scan_cache(
+    opts: CacheOptions,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+) -> Option<ScanCache> {
+    if !opts.enabled {
+        return None;
+    }
+    let Some(file) = default_scan_cache_file() else {
+        warnings.warn_msg(tr(
+            "无法确定缓存目录（未设置 HOME/XDG_CACHE_HOME），本次不使用缓存",
+        ));
+        return None;
+    };
+    Some(ScanCache::open(file, opts.refresh, excludes, warnings))
+}
+
+impl ScanCache {
+    pub(crate) fn open(
+        file: PathBuf,
+        refresh: bool,
+        excludes: &ExcludeSet,
+        warnings: &WarningTracker,
+    ) -> Self {
+        let mut previous = HashMap::new();
+        if !refresh {
+            match fs::read(&file) {
+                Ok(bytes) => match serde_json::from_slice::<ScanCacheFile>(&bytes) {
+                    Ok(f) if f.version == SCAN_CACHE_VERSION => {
+                        previous = f
+                            .dirs
+                            .into_iter()
+                            .map(|(k, v)| (PathBuf::from(k), v))
+                            .collect();
+                    }
+                    Ok(_) => {}
+                    Err(e) => warnings.warn_io(CTX_CACHE_READ, &file, &e),
+                },
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                Err(e) => warnings.warn_io(CTX_CACHE_READ, &file, &e),
+            }
+        }
+        Self {
+            file,
+            previous,
+            fresh: Mutex::new(HashMap::new()),
+            excludes: excludes.fingerprint(),
+        }
+    }
+
+    pub(crate) fn fresh(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, CachedDir>> {
+        self.fresh.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// stamp 与排除规则都一致时，返回缓存的本层叶子统计与子目录列表（子目录会重新 stat）。
+    pub(crate) fn reuse_level(
+        &self,
+        path: &Path,
+        stamp: DirStamp,
+    ) -> Option<(DirStats, Vec<(PathBuf, DirStamp)>)> {
+        let key = absify_for_compare(path);
+        let cached = self.previous.get(&key)?;
+        if cached.stamp != stamp || cached.excludes != self.excludes {
+            return None;
+        }
+
+        let mut subdirs = Vec::with_capacity(cached.subdirs.len());
+        for name in &cached.subdirs {
+            let p = path.join(name);
+            // mtime 未变时子目录不应消失或变成文件；一旦不符，保守地回退到完整读取
+            let m = fs::symlink_metadata(&p).ok().filter(|m| m.is_dir())?;
+            subdirs.push((p, DirStamp::from_meta(&m)));
+        }
+
+        self.fresh().insert(key, cached.clone());
+        Some((cached.level, subdirs))
+    }
+
+    pub(crate) fn record(
+        &self,
+        path: &Path,
+        stamp: DirStamp,
+        level: DirStats,
+        subdirs: &[(PathBuf, DirStamp)],
+    ) {
+        // 非 UTF-8 名称无法无损写入 JSON，这类目录不缓存
+        let subdirs: Option<Vec<String>> = subdirs
+            .iter()
+            .map(|(p, _)| p.file_name()?.to_str().map(str::to_owned))
+            .collect();
+        let Some(subdirs) = subdirs else {
+            return;
+        };
+        self.fresh().insert(
+            absify_for_compare(path),
+            CachedDir {
+                stamp,
+                excludes: self.excludes,
+                level,
+                subdirs,
+            },
+        );
+    }
+
+    /// 写回缓存：root 之下的记录整体替换为本次结果（删除的目录随之清除），其余保留。
+    pub(crate) fn save(self, root: &Path, warnings: &WarningTracker) {
+        let root_abs = absify_for_compare(root);
+        let fresh = self.fresh.into_inner().unwrap_or_else(|e| e.into_inner());
+        let dirs: HashMap<String, CachedDir> = self
+            .previous
+            .into_iter()
+            .filter(|(k, _)| !k.starts_with(&root_abs))
+            .chain(fresh)
+            .filter_map(|(k, v)| k.to_str().map(|s| (s.to_owned(), v)))
+            .collect();
+        let out = ScanCacheFile {
+            version: SCAN_CACHE_VERSION,
+            dirs,
+        };
+
+        let res = (|| -> io::Result<()> {
+            if let Some(parent) = self.file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // 先写临时文件再 rename，避免中途失败留下损坏的缓存
+            let tmp = self.file.with_extension("json.tmp");
+            fs::write(&tmp, serde_json::to_vec(&out)?)?;
+            fs::rename(&tmp, &self.file)
+        })();
+        if let Err(e) = res {
+            warnings.warn_io(CTX_CACHE_WRITE, &self.file, &e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{ScanHooks, analyze_directory};
+    use crate::test_util::TempDirGuard;
+
+    #[test]
+    fn test_scan_cache_reuses_unchanged_dirs_and_detects_changes() {
+        let tmp = TempDirGuard::new("yp_scan_cache");
+        let data = tmp.path().join("data");
+        let sub = data.join("sub");
+        fs::create_dir_all(&sub).expect("failed to create sub dir");
+        fs::write(data.join("a"), b"aaaa").expect("failed to write a");
+        fs::write(sub.join("b"), b"bb").expect("failed to write b");
+        let cache_file = tmp.path().join("cache.json");
+
+        let warnings = WarningTracker::new();
+        let excludes = ExcludeSet {
+            patterns: Vec::new(),
+            has_abs: false,
+        };
+        let run = |refresh: bool| {
+            let cache = ScanCache::open(cache_file.clone(), refresh, &excludes, &warnings);
+            let hooks = ScanHooks {
+                cache: Some(&cache),
+                ..Default::default()
+            };
+            let report = analyze_directory(tmp.path(), false, &excludes, &warnings, hooks)
+                .expect("analyze_directory should succeed");
+            let reused = cache.previous.len();
+            cache.save(tmp.path(), &warnings);
+            let data = report
+                .entries()
+                .find(|e| e.name() == "data")
+                .expect("entry 'data' should exist")
+                .to_entry();
+            (data, reused)
+        };
+
+        let (first, reused) = run(false);
+        assert_eq!(reused, 0, "首次运行没有缓存");
+        assert_eq!((first.size, first.file_count, first.dir_count), (6, 2, 1));
+
+        // 第二次：缓存命中，结果一致
+        let (second, reused) = run(false);
+        assert_eq!(reused, 2, "data 与 sub 两个目录都应有缓存记录");
+        assert_eq!(second.size, first.size);
+
+        // 在子目录新增文件：sub 的 mtime 改变，即使 data 的缓存命中也能发现
+        fs::write(sub.join("c"), b"ccc").expect("failed to write c");
+        let (third, _) = run(false);
+        assert_eq!((third.size, third.file_count), (9, 3));
+
+        // 原地改写文件不改变目录 mtime：缓存命中时沿用旧大小（已知局限）
+        let (_, reused) = run(false);
+        assert_eq!(reused, 2);
+        fs::write(data.join("a"), b"aaaaaaaa").expect("failed to rewrite a");
+        let (stale, _) = run(false);
+        assert_eq!((stale.size, stale.file_count), (9, 3));
+
+        // --refresh 忽略旧缓存，原地改写随之反映
+        let (fourth, reused) = run(true);
+        assert_eq!(reused, 0);
+        assert_eq!(fourth.size, 13);
+    }
+}
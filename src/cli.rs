@@ -0,0 +1,1196 @@
+//! 命令行入口：参数解析与模式分发。
+
+use crate::budget::{BudgetVisitor, Budgets, Violation, check_budgets};
+use crate::cache::{CacheOptions, ScanCache, open_scan_cache};
+use crate::config::{Settings, load_config};
+use crate::dupes::find_duplicates;
+use crate::empty::find_empty;
+use crate::error::AppError;
+use crate::exclude::{ExcludeSet, compile_excludes};
+use crate::filter::TypeFilter;
+use crate::format::{SizeFormat, SizeUnits, parse_size};
+use crate::histogram::{HistogramScale, build_histogram};
+use crate::history::{
+    DepthCollector, append_record, default_history_file, history_key, load_history,
+};
+use crate::i18n::{Lang, set_lang, tr, trf, trn};
+use crate::links::find_broken_links;
+use crate::progress::{CLEAR_LINE, Progress, progress_enabled, with_progress};
+use crate::reclaim::find_reclaimable;
+use crate::render::{
+    RenderOptions, output_broken_links, output_broken_links_json, output_budget_violations,
+    output_duplicates, output_duplicates_json, output_empty, output_empty_json, output_histogram,
+    output_histogram_json, output_history, output_history_json, output_json, output_json_summary,
+    output_reclaimable, output_reclaimable_json, output_summary, output_text, output_tree,
+};
+use crate::scan::{FollowSymlinks, ScanHooks, analyze_directory};
+use crate::sort::{SortKey, SortSpec};
+use crate::threads::{ThreadConfig, configure_thread_pool};
+use crate::visit::{Visitor, VisitorChain};
+use crate::warnings::{CTX_HISTORY_WRITE, WarningTracker, print_warning_summary};
+use crate::watch::watch_loop;
+use clap::parser::ValueSource;
+use clap::{Arg, Command};
+use colored::*;
+use std::cell::Cell;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use thiserror::Error;
+
+// ---- CliError ----
+//
+// 配置、预算规则与扫描历史只有命令行会读取，相应的错误不放进库的公开 AppError。
+/// 命令行运行失败的原因：扫描错误，或只在命令行中出现的输入文件错误。
+#[derive(Debug, Error)]
+pub(crate) enum CliError {
+    /// 扫描或输出失败
+    #[error(transparent)]
+    App(#[from] AppError),
+
+    /// 预算规则文件无法读取或格式有误
+    #[error("{}", trf("预算规则文件 {}: {}", &[&.path.display(), .msg]))]
+    BudgetFile {
+        /// 规则文件路径
+        path: PathBuf,
+        /// 出错原因（含行号）
+        msg: String,
+    },
+
+    /// 配置文件无法读取、解析或取值无效
+    #[error("{}", trf("配置文件 {}: {}", &[&.path.display(), .msg]))]
+    Config {
+        /// 配置文件路径
+        path: PathBuf,
+        /// 出错原因
+        msg: String,
+    },
+
+    /// `--profile` 指定的 profile 在配置文件中不存在
+    #[error("{}", trf("未定义的 profile: {}", &[.0]))]
+    UnknownProfile(String),
+
+    /// 扫描历史文件无法读取
+    #[error("{}", trf("无法读取扫描历史: {} ({})", &[&.path.display(), .source]))]
+    HistoryFile {
+        /// 历史文件路径
+        path: PathBuf,
+        /// 底层 IO 错误
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// `Result<T, CliError>` 的简写。
+pub(crate) type CliResult<T> = Result<T, CliError>;
+
+// ---- Ctrl-C ----
+//
+// 第一次 SIGINT 只置位 CANCELLED，遍历尽快收尾并输出已得到的部分结果；
+// 第二次 SIGINT 直接退出。
+
+pub(crate) static CANCELLED: AtomicBool = AtomicBool::new(false);
+pub(crate) const EXIT_INTERRUPTED: i32 = 130;
+
+// ---- 预算 ----
+//
+// 超出预算不是错误：结果照常输出，之后以 EXIT_BUDGET_EXCEEDED 退出，
+// 便于 CI 区分"超限"（3）与"运行失败"（1）、"参数错误"（2）。
+
+pub(crate) static BUDGET_EXCEEDED: AtomicBool = AtomicBool::new(false);
+pub(crate) const EXIT_BUDGET_EXCEEDED: i32 = 3;
+
+/// 预算与 --record 只作用于 report 与 tree 模式
+const REPORT_ONLY: [&str; 6] = [
+    "broken-links",
+    "duplicates",
+    "empty",
+    "reclaimable",
+    "histogram",
+    "watch",
+];
+
+/// 规则文件的逐项统计挂在主扫描上；只有 --max-total 时不需要 visitor
+pub(crate) fn budget_visitor<'a>(
+    root: &'a Path,
+    budgets: &'a Budgets,
+) -> Option<BudgetVisitor<'a>> {
+    (!budgets.rules.is_empty()).then(|| BudgetVisitor::new(root, &budgets.rules))
+}
+
+/// 挂在主扫描上的预算与 --record 统计
+pub(crate) fn scan_visitors<'a>(
+    budget: &'a Option<BudgetVisitor>,
+    collector: &'a Option<DepthCollector>,
+) -> VisitorChain<'a> {
+    let mut v: Vec<&dyn Visitor> = Vec::new();
+    if let Some(b) = budget {
+        v.push(b);
+    }
+    if let Some(c) = collector {
+        v.push(c);
+    }
+    VisitorChain(v)
+}
+
+/// 由主扫描的总大小与 visitor 的统计得出违规项
+pub(crate) fn run_budget_check(
+    root: &Path,
+    total: u64,
+    budgets: &Budgets,
+    visitor: Option<BudgetVisitor>,
+    warnings: &WarningTracker,
+) -> Vec<Violation> {
+    if budgets.is_empty() {
+        return Vec::new();
+    }
+    let violations = check_budgets(root, total, budgets, visitor, warnings);
+    if !violations.is_empty() {
+        BUDGET_EXCEEDED.store(true, Ordering::Relaxed);
+    }
+    violations
+}
+
+// ---- 扫描历史 ----
+//
+// --record 在主扫描上挂 DepthCollector，收集记录深度内各目录的总量，追加到历史文件；
+// 被 Ctrl-C 中断的扫描不记录，免得部分结果在趋势中表现为骤降。写入失败只计告警。
+
+pub(crate) fn run_record(collector: DepthCollector, total: u64, warnings: &WarningTracker) {
+    let Some(file) = default_history_file() else {
+        warnings.warn_msg(tr(
+            "无法确定历史文件位置（未设置 HOME/XDG_DATA_HOME），本次不记录",
+        ));
+        return;
+    };
+    if let Err(e) = append_record(&file, &collector.into_record(total)) {
+        warnings.warn_io(CTX_HISTORY_WRITE, &file, &e);
+    }
+}
+
+/// `yp history`：显示目录大小的历史、增长速率与达到 `until` 的预测。
+pub(crate) fn run_history_mode(
+    path: &str,
+    until: Option<u64>,
+    json_output: bool,
+    size_fmt: SizeFormat,
+) -> CliResult<()> {
+    let key = history_key(Path::new(path));
+    let points = match default_history_file() {
+        Some(file) => load_history(&file, &key)
+            .map_err(|source| CliError::HistoryFile { path: file, source })?,
+        None => Vec::new(),
+    };
+    if json_output {
+        output_history_json(&key, &points, until)?;
+    } else {
+        output_history(&key, &points, until, size_fmt);
+    }
+    Ok(())
+}
+
+pub(crate) fn install_interrupt_handler(warnings: &WarningTracker) {
+    let res = ctrlc::set_handler(|| {
+        if CANCELLED.swap(true, Ordering::SeqCst) {
+            eprintln!("{}", CLEAR_LINE);
+            std::process::exit(EXIT_INTERRUPTED);
+        }
+    });
+    if let Err(e) = res {
+        warnings.warn_msg(&trf("无法注册 Ctrl-C 处理器，中断将直接退出 ({})", &[&e]));
+    }
+}
+
+pub(crate) fn scan_hooks<'a>(
+    progress: Option<&'a Progress>,
+    cache: Option<&'a ScanCache>,
+    follow: FollowSymlinks,
+) -> ScanHooks<'a> {
+    ScanHooks {
+        progress,
+        cancel: Some(&CANCELLED),
+        cache,
+        visitor: None,
+        follow,
+    }
+}
+
+// ---- 模式分发 ----
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_tree_mode(
+    path: &str,
+    recursive: bool,
+    follow: FollowSymlinks,
+    sort: SortSpec,
+    render: RenderOptions,
+    cache_opts: CacheOptions,
+    budgets: &Budgets,
+    record: Option<usize>,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+) -> CliResult<()> {
+    let root = Path::new(path);
+    let budget = budget_visitor(root, budgets);
+    let collector = record.map(|depth| DepthCollector::new(root, depth));
+    let visitors = scan_visitors(&budget, &collector);
+
+    // 与 report 模式一致：recursive 需要逐项条目，持久缓存只用于非递归（仅顶层条目）；
+    // 跟随全部链接时每层的统计口径不同，同样不使用缓存
+    let scan_cache =
+        if recursive || follow == FollowSymlinks::All || visitors.as_visitor().is_some() {
+            None
+        } else {
+            open_scan_cache(cache_opts, excludes, warnings)
+        };
+    let report = with_progress(render.show_progress, render.size_fmt, warnings, |p| {
+        analyze_directory(
+            root,
+            recursive,
+            excludes,
+            warnings,
+            ScanHooks {
+                visitor: visitors.as_visitor(),
+                ..scan_hooks(p, scan_cache.as_ref(), follow)
+            },
+        )
+    })?;
+    if let Some(c) = scan_cache
+        && !report.incomplete
+    {
+        c.save(root, warnings);
+    }
+
+    output_tree(&report, render, sort, None);
+    let violations = run_budget_check(root, report.total_size, budgets, budget, warnings);
+    if !violations.is_empty() {
+        output_budget_violations(&violations, render.size_fmt);
+    }
+    if let Some(c) = collector
+        && !report.incomplete
+    {
+        run_record(c, report.total_size, warnings);
+    }
+    print_warning_summary(warnings);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_report_mode(
+    path: &str,
+    recursive: bool,
+    follow: FollowSymlinks,
+    sort: SortSpec,
+    json_output: bool,
+    summary_only: bool,
+    render: RenderOptions,
+    cache_opts: CacheOptions,
+    budgets: &Budgets,
+    record: Option<usize>,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+) -> CliResult<()> {
+    let root = Path::new(path);
+    let budget = budget_visitor(root, budgets);
+    let collector = record.map(|depth| DepthCollector::new(root, depth));
+    let visitors = scan_visitors(&budget, &collector);
+
+    // recursive report 需要逐项条目，不经过缓存路径；此时不加载也不写回，避免清空已有记录。
+    // 缓存记录的是不跟随链接的统计，--follow-symlinks all 时同样绕过；
+    // 预算规则与 --record 需要逐项回调，缓存命中的目录给不出条目，也绕过
+    let scan_cache =
+        if recursive || follow == FollowSymlinks::All || visitors.as_visitor().is_some() {
+            None
+        } else {
+            open_scan_cache(cache_opts, excludes, warnings)
+        };
+    let mut report = with_progress(render.show_progress, render.size_fmt, warnings, |p| {
+        analyze_directory(
+            root,
+            recursive,
+            excludes,
+            warnings,
+            ScanHooks {
+                visitor: visitors.as_visitor(),
+                ..scan_hooks(p, scan_cache.as_ref(), follow)
+            },
+        )
+    })?;
+    if let Some(c) = scan_cache
+        && !report.incomplete
+    {
+        c.save(root, warnings);
+    }
+
+    report.sort(sort);
+    report.retain(|e| render.types.matches(e.kind()));
+    let violations = run_budget_check(root, report.total_size, budgets, budget, warnings);
+
+    if json_output {
+        if summary_only {
+            output_json_summary(&report, &violations)?;
+        } else {
+            output_json(&report, &violations)?;
+        }
+    } else {
+        if summary_only {
+            output_summary(&report, render.size_fmt);
+        } else {
+            output_text(&report, &render, None);
+        }
+        if !violations.is_empty() {
+            output_budget_violations(&violations, render.size_fmt);
+        }
+    }
+    if let Some(c) = collector
+        && !report.incomplete
+    {
+        run_record(c, report.total_size, warnings);
+    }
+
+    print_warning_summary(warnings);
+    Ok(())
+}
+
+pub(crate) fn run_broken_links_mode(
+    path: &str,
+    follow: FollowSymlinks,
+    json_output: bool,
+    render: RenderOptions,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+) -> CliResult<()> {
+    let root = Path::new(path);
+    let (links, incomplete) =
+        with_progress(render.show_progress, render.size_fmt, warnings, |p| {
+            find_broken_links(root, excludes, warnings, scan_hooks(p, None, follow))
+        })?;
+    if json_output {
+        output_broken_links_json(root, &links, incomplete)?;
+    } else {
+        output_broken_links(root, &links, incomplete);
+    }
+    print_warning_summary(warnings);
+    Ok(())
+}
+
+pub(crate) fn run_duplicates_mode(
+    path: &str,
+    follow: FollowSymlinks,
+    json_output: bool,
+    render: RenderOptions,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+) -> CliResult<()> {
+    let root = Path::new(path);
+    let (sets, incomplete) = with_progress(render.show_progress, render.size_fmt, warnings, |p| {
+        find_duplicates(root, excludes, warnings, scan_hooks(p, None, follow))
+    })?;
+    if json_output {
+        output_duplicates_json(root, &sets, incomplete)?;
+    } else {
+        output_duplicates(root, &sets, incomplete, render.size_fmt);
+    }
+    print_warning_summary(warnings);
+    Ok(())
+}
+
+pub(crate) fn run_empty_mode(
+    path: &str,
+    follow: FollowSymlinks,
+    json_output: bool,
+    render: RenderOptions,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+) -> CliResult<()> {
+    let root = Path::new(path);
+    let (groups, incomplete) =
+        with_progress(render.show_progress, render.size_fmt, warnings, |p| {
+            find_empty(root, excludes, warnings, scan_hooks(p, None, follow))
+        })?;
+    if json_output {
+        output_empty_json(root, &groups, incomplete)?;
+    } else {
+        output_empty(root, &groups, incomplete);
+    }
+    print_warning_summary(warnings);
+    Ok(())
+}
+
+pub(crate) fn run_reclaimable_mode(
+    path: &str,
+    follow: FollowSymlinks,
+    json_output: bool,
+    render: RenderOptions,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+) -> CliResult<()> {
+    let root = Path::new(path);
+    let (items, totals, incomplete) =
+        with_progress(render.show_progress, render.size_fmt, warnings, |p| {
+            find_reclaimable(root, excludes, warnings, scan_hooks(p, None, follow))
+        })?;
+    if json_output {
+        output_reclaimable_json(root, &items, &totals, incomplete)?;
+    } else {
+        output_reclaimable(root, &items, &totals, incomplete, render.size_fmt);
+    }
+    print_warning_summary(warnings);
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_histogram_mode(
+    path: &str,
+    scale: HistogramScale,
+    follow: FollowSymlinks,
+    json_output: bool,
+    render: RenderOptions,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+) -> CliResult<()> {
+    let root = Path::new(path);
+    let (hist, incomplete) = with_progress(render.show_progress, render.size_fmt, warnings, |p| {
+        build_histogram(root, scale, excludes, warnings, scan_hooks(p, None, follow))
+    })?;
+    if json_output {
+        output_histogram_json(root, &hist, incomplete)?;
+    } else {
+        output_histogram(root, &hist, incomplete, &render);
+    }
+    print_warning_summary(warnings);
+    Ok(())
+}
+
+/// `--watch`：按间隔重新扫描并原位刷新文本或 tree 视图；不读写扫描缓存。
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_watch_mode(
+    path: &str,
+    interval: Duration,
+    tree_mode: bool,
+    recursive: bool,
+    follow: FollowSymlinks,
+    sort: SortSpec,
+    render: RenderOptions,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+) -> CliResult<()> {
+    let root = Path::new(path);
+    // 每轮单独计数且不写 stderr：同一个不可读目录不会每轮刷屏，也不会在汇总里重复计数；
+    // 本轮的告警数显示在重绘的画面里
+    let round_warnings = Cell::new(0usize);
+    watch_loop(
+        interval,
+        &CANCELLED,
+        || {
+            let round = WarningTracker::collecting();
+            let report = with_progress(render.show_progress, render.size_fmt, &round, |p| {
+                analyze_directory(
+                    root,
+                    recursive,
+                    excludes,
+                    &round,
+                    scan_hooks(p, None, follow),
+                )
+            });
+            round_warnings.set(round.warning_total());
+            let mut report = report?;
+            if !tree_mode {
+                report.sort(sort);
+                report.retain(|e| render.types.matches(e.kind()));
+            }
+            Ok(report)
+        },
+        |report, growth| {
+            if tree_mode {
+                output_tree(report, render, sort, growth);
+            } else {
+                output_text(report, &render, growth);
+            }
+            println!(
+                "{}",
+                trf("每 {} 秒刷新，Ctrl-C 退出", &[&interval.as_secs()]).dimmed()
+            );
+            let n = round_warnings.get();
+            if n > 0 {
+                println!(
+                    "{}",
+                    trn("本轮扫描产生 {} 条警告，部分结果可能偏小", n as u64, &[&n]).yellow()
+                );
+            }
+        },
+    )?;
+    // 在 watch 中 Ctrl-C 是正常的退出方式，不按中断返回 130
+    CANCELLED.store(false, Ordering::Relaxed);
+    print_warning_summary(warnings);
+    Ok(())
+}
+
+// ---- CLI ----
+
+pub fn main() {
+    if let Err(e) = run() {
+        eprintln!("{} {}", tr("错误:").red().bold(), e);
+        std::process::exit(1);
+    }
+    if CANCELLED.load(Ordering::Relaxed) {
+        std::process::exit(EXIT_INTERRUPTED);
+    }
+    if BUDGET_EXCEEDED.load(Ordering::Relaxed) {
+        std::process::exit(EXIT_BUDGET_EXCEEDED);
+    }
+}
+
+/// 帮助文本在构建 Command 时就已确定语言，因此 `--lang` 先于 clap 从原始参数中取出；
+/// clap 随后仍会校验该参数。
+fn lang_from_args() -> Option<Lang> {
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        let arg = arg.to_string_lossy();
+        if arg == "--" {
+            break;
+        }
+        if let Some(v) = arg.strip_prefix("--lang=") {
+            return Lang::from_arg(v);
+        }
+        if arg == "--lang" {
+            return Lang::from_arg(&args.next()?.to_string_lossy());
+        }
+    }
+    None
+}
+
+pub(crate) fn run() -> CliResult<()> {
+    set_lang(lang_from_args().unwrap_or_else(Lang::from_env));
+    let matches = Command::new("yp")
+        .name(tr("YP - 目录空间查看器"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .about(tr("一个高性能的目录空间占用查看工具\nPACI mail@pa.ci"))
+        .arg(
+            Arg::new("path")
+                .short('p')
+                .long("path")
+                .value_name("PATH")
+                .help(tr("指定要分析的目录路径"))
+                .default_value("."),
+        )
+        .arg(
+            Arg::new("sort")
+                .long("sort")
+                .value_name("KEY")
+                .help(
+                    tr("排序方式：size（大小降序，默认）、name（名称自然序）、count（文件数降序）、\n\
+                     mtime（修改时间，最新在前）、ext（扩展名）、none（不排序）。\n\
+                     对文本、tree 与 JSON 输出一致生效"),
+                )
+                .value_parser(["size", "name", "count", "mtime", "ext", "none"])
+                .ignore_case(true)
+                .default_value("size"),
+        )
+        .arg(
+            Arg::new("reverse")
+                .long("reverse")
+                .help(tr("反转排序结果"))
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("no-reverse"),
+        )
+        .arg(
+            Arg::new("no-reverse")
+                .long("no-reverse")
+                .help(tr("不反转排序结果（覆盖配置文件中的 reverse）"))
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("reverse"),
+        )
+        .arg(
+            Arg::new("no-sort")
+                .long("no-sort")
+                .help(tr("禁用排序，等同于 --sort none"))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("json")
+                .short('j')
+                .long("json")
+                .help(tr("以 JSON 格式输出"))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no-chart")
+                .long("no-chart")
+                .help(tr("禁用 ASCII 条形图（默认启用）"))
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("chart"),
+        )
+        .arg(
+            Arg::new("chart")
+                .long("chart")
+                .help(tr("显示 ASCII 条形图（覆盖配置文件中的 chart）"))
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("no-chart"),
+        )
+        .arg(
+            Arg::new("recursive")
+                .short('r')
+                .long("recursive")
+                .help(tr("递归显示所有子目录（tree 模式下展开所有层级；默认不跟随符号链接）"))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("tree")
+                .short('t')
+                .long("tree")
+                .help(
+                    tr("以树状方式显示每个文件/目录及其大小（与 -r 结合递归展开；默认不跟随符号链接）"),
+                )
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("follow-symlinks")
+                .long("follow-symlinks")
+                .value_name("MODE")
+                .help(
+                    tr("跟随符号链接，统计链接目标：all（遍历中的全部链接，默认）、\n\
+                     cmdline（仅 -p 给出的路径本身）。按 (设备, inode) 识别循环与重复，同一目录只统计一次；\n\
+                     失效链接给出警告并按链接自身计；经链接到达的条目名后标记 @"),
+                )
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("all")
+                .value_parser(["all", "cmdline"])
+                .ignore_case(true),
+        )
+        .arg(
+            Arg::new("follow-all")
+                .short('L')
+                .help(tr("等同于 --follow-symlinks=all"))
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["follow-symlinks", "follow-cmdline"]),
+        )
+        .arg(
+            Arg::new("follow-cmdline")
+                .short('H')
+                .help(tr("等同于 --follow-symlinks=cmdline"))
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("follow-symlinks"),
+        )
+        .arg(
+            Arg::new("broken-links")
+                .long("broken-links")
+                .help(tr("递归列出路径下所有失效（悬空）的符号链接及其指向，可与 -j、-e 组合"))
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["tree", "summary"]),
+        )
+        .arg(
+            Arg::new("duplicates")
+                .long("duplicates")
+                .help(
+                    tr("递归查找内容相同的文件：先按大小分组，再依次比较部分与全文哈希；\n\
+                     按可回收空间降序列出每组，同一 inode 的硬链接与空文件不计，可与 -j、-e 组合"),
+                )
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["tree", "summary", "broken-links"]),
+        )
+        .arg(
+            Arg::new("empty")
+                .long("empty")
+                .help(
+                    tr("递归列出空目录（含只包含空目录的目录，只报告最外层）与零字节文件，\n\
+                     按父目录分组；含被排除条目的目录不算空，可与 -j、-e 组合"),
+                )
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["tree", "summary", "broken-links", "duplicates"]),
+        )
+        .arg(
+            Arg::new("reclaimable")
+                .long("reclaimable")
+                .help(
+                    tr("递归识别可清理的缓存与构建目录：node_modules、target、.venv、__pycache__、\n\
+                     .gradle、.cache、dist 与 Docker 构建缓存（按名称及 Cargo.toml、package.json\n\
+                     等标记文件判断）；按类别汇总大小并列出最大的若干项及其项目根"),
+                )
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["tree", "summary", "broken-links", "duplicates", "empty"]),
+        )
+        .arg(
+            Arg::new("histogram")
+                .long("histogram")
+                .value_name("SCALE")
+                .help(
+                    tr("递归统计普通文件的大小分布：pow2（按 2 的幂分桶，默认）或 decade（按 10 的幂）；\n\
+                     每个桶给出文件数与总字节数，条形图按文件数绘制（--no-chart 关闭）"),
+                )
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("pow2")
+                .value_parser(["pow2", "decade"])
+                .ignore_case(true)
+                .conflicts_with_all([
+                    "tree",
+                    "summary",
+                    "broken-links",
+                    "duplicates",
+                    "empty",
+                    "reclaimable",
+                ]),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .value_name("SECS")
+                .help(
+                    tr("每隔 SECS 秒（默认 2）重新扫描并原位刷新文本或 tree 视图，\n\
+                     高亮自上次刷新以来增长的条目并显示增量与增长速率；Ctrl-C 退出"),
+                )
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("2")
+                .value_parser(clap::value_parser!(u64).range(1..))
+                .conflicts_with_all([
+                    "json",
+                    "summary",
+                    "broken-links",
+                    "duplicates",
+                    "empty",
+                    "reclaimable",
+                    "histogram",
+                ]),
+        )
+        .arg(
+            Arg::new("max-total")
+                .long("max-total")
+                .value_name("SIZE")
+                .help(
+                    tr("总大小上限（如 500M、2G、1.5GB）；超出时在输出后列出并以退出码 3 结束，\n\
+                     JSON 输出增加 budget_violations 字段"),
+                )
+                .value_parser(|s: &str| parse_size(s).ok_or(trf("无效的大小 '{}'", &[&s])))
+                .conflicts_with_all(REPORT_ONLY),
+        )
+        .arg(
+            Arg::new("budgets")
+                .long("budgets")
+                .value_name("FILE")
+                .help(
+                    tr("预算规则文件，每行 `<路径或模式> <= <大小> [each]`，路径相对于扫描根，\n\
+                     如 `dist/ <= 50M`、`assets/**/*.png <= 5M each`；违规处理同 --max-total"),
+                )
+                .conflicts_with_all(REPORT_ONLY),
+        )
+        .arg(
+            Arg::new("record")
+                .long("record")
+                .value_name("DEPTH")
+                .help(
+                    tr("扫描后把扫描根及 DEPTH 层（默认 2）以内各目录的总大小追加到历史文件\n\
+                     （YP_HISTORY_FILE，默认 ~/.local/share/yp/history.jsonl），供 yp history 查看；\n\
+                     数据取自同一次扫描（不使用扫描缓存），被中断的扫描不记录"),
+                )
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("2")
+                .value_parser(clap::value_parser!(usize))
+                .conflicts_with_all(REPORT_ONLY),
+        )
+        .arg(
+            Arg::new("type")
+                .long("type")
+                .value_name("TYPES")
+                .help(
+                    tr("只列出指定类型的条目，逗号分隔：f 文件、d 目录、l 符号链接、s 套接字、\n\
+                     p 命名管道、c 字符设备、b 块设备（如 --type f,l）。\n\
+                     不影响总大小与目录统计；tree 模式下目录始终保留以显示层级"),
+                )
+                .value_parser(TypeFilter::parse),
+        )
+        .arg(
+            Arg::new("icon")
+                .long("icon")
+                .help(tr("tree 模式显示图标（📁/📄）"))
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("no-icon"),
+        )
+        .arg(
+            Arg::new("no-icon")
+                .long("no-icon")
+                .help(tr("不显示图标（覆盖配置文件中的 icon）"))
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("icon"),
+        )
+        .arg(
+            Arg::new("counts")
+                .long("counts")
+                .help(tr("显示每个目录的递归文件数/目录数（inode 占用），文本与 tree 模式均适用"))
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("no-counts"),
+        )
+        .arg(
+            Arg::new("no-counts")
+                .long("no-counts")
+                .help(tr("不显示文件数/目录数（覆盖配置文件中的 counts）"))
+                .action(clap::ArgAction::SetTrue)
+                .overrides_with("counts"),
+        )
+        .arg(
+            Arg::new("progress")
+                .long("progress")
+                .help(tr("扫描时在 stderr 显示进度行（默认仅当 stderr 为终端时启用）"))
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("no-progress"),
+        )
+        .arg(
+            Arg::new("no-progress")
+                .long("no-progress")
+                .help(tr("禁用进度行"))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("threads")
+                .long("threads")
+                .value_name("N")
+                .help(
+                    tr("工作线程数（默认等于 CPU 核数，也可用环境变量 YP_THREADS 设置）。\n\
+                     机械盘/繁忙的 NFS 上调小可减少并发 I/O，NVMe 上可调大。\n\
+                     YP_PAR_MIN_ENTRIES 仅控制子项数达到多少才交给线程池，不改变线程数"),
+                )
+                .value_parser(clap::value_parser!(u32).range(1..)),
+        )
+        .arg(
+            Arg::new("nice")
+                .long("nice")
+                .help(
+                    tr("低影响模式：单线程、空闲 I/O 优先级、最低 CPU 优先级（也可用 YP_NICE=1）。\n\
+                     与 --threads 同时指定时以 --threads 的线程数为准"),
+                )
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("cache")
+                .long("cache")
+                .help(
+                    tr("启用持久扫描缓存（也可用 YP_CACHE=1）：目录 mtime 未变时沿用缓存的统计，跳过其 read_dir 与文件 stat。\n\
+                     仅作用于非 -r 的列表与 tree 模式；原地改写的文件不改变目录 mtime，需 --refresh 才能反映"),
+                )
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no-cache")
+                .long("no-cache")
+                .help(tr("禁用持久扫描缓存（覆盖 --cache 与 YP_CACHE）"))
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["cache", "refresh"]),
+        )
+        .arg(
+            Arg::new("refresh")
+                .long("refresh")
+                .help(tr("忽略已有缓存完整扫描，并用结果重写缓存"))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("summary")
+                .short('S')
+                .long("summary")
+                .help(tr("只显示目录/总大小/项目数，不显示详细条目"))
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("units")
+                .long("units")
+                .value_name("UNITS")
+                .help(tr("大小单位：iec（KiB/MiB，默认）、si（kB/MB）、bytes（精确字节数）"))
+                .value_parser(["iec", "si", "bytes"])
+                .ignore_case(true)
+                .default_value("iec"),
+        )
+        .arg(
+            Arg::new("unit-fixed")
+                .long("unit-fixed")
+                .value_name("UNIT")
+                .help(tr("所有行使用固定单位（K/M/G/T），便于对齐比较；--units bytes 时忽略"))
+                .value_parser(["K", "M", "G", "T"])
+                .ignore_case(true),
+        )
+        .arg(
+            Arg::new("color")
+                .long("color")
+                .value_name("WHEN")
+                .help(tr("彩色输出：auto（默认，遵循 NO_COLOR 与终端检测）、always、never"))
+                .value_parser(["auto", "always", "never"])
+                .ignore_case(true)
+                .default_value("auto"),
+        )
+        .arg(
+            Arg::new("lang")
+                .long("lang")
+                .value_name("LANG")
+                .help(tr(
+                    "界面语言：en 或 zh（默认按 LC_ALL、LC_MESSAGES、LANG 判断，zh 开头为中文）",
+                ))
+                .value_parser(["en", "zh"])
+                .ignore_case(true)
+                .global(true),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .value_name("NAME")
+                .help(
+                    tr("使用配置文件中 [profiles.NAME] 定义的一组选项，叠加在配置文件的默认值之上"),
+                ),
+        )
+        .arg(
+            Arg::new("no-config")
+                .long("no-config")
+                .help(
+                    tr("不读取配置文件（全局 ~/.config/yp/config.toml 或 YP_CONFIG，\n\
+                     及从当前目录向上找到的 .yp.toml）"),
+                )
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("profile"),
+        )
+        .arg(
+            Arg::new("exclude")
+                .short('e')
+                .long("exclude")
+                .value_name("PATTERN")
+                .help(
+                    tr("排除指定文件/文件夹（可多次使用）。\n\
+                     支持：\n\
+                     • 名称模式（如 node_modules）：匹配任意层级同名条目\n\
+                     • 路径模式（含 / 则视为路径）：统一绝对化后比较，\n\
+                       相对路径以 CWD 为基准；不处理 '..' 归一化。\n\
+                     symlink 默认不跟随，size 取链接自身元数据长度。"),
+                )
+                .action(clap::ArgAction::Append),
+        )
+        .subcommand(
+            Command::new("history")
+                .about(tr("显示 --record 记录的目录大小变化、增长速率与达到指定大小的预计时间"))
+                .arg(
+                    Arg::new("path")
+                        .value_name("PATH")
+                        .help(tr("要查看的目录（扫描根或其记录深度内的子目录）"))
+                        .default_value("."),
+                )
+                .arg(
+                    Arg::new("until")
+                        .long("until")
+                        .value_name("SIZE")
+                        .help(tr("按线性趋势预测何时达到该大小（如 100G）"))
+                        .value_parser(|s: &str| parse_size(s).ok_or(trf("无效的大小 '{}'", &[&s]))),
+                )
+                .arg(
+                    Arg::new("json")
+                        .short('j')
+                        .long("json")
+                        .help(tr("以 JSON 格式输出"))
+                        .action(clap::ArgAction::SetTrue),
+                ),
+        )
+        .get_matches();
+
+    // 配置文件提供默认值；命令行显式给出的参数优先，-e 与配置中的 exclude 累加
+    let config = if matches.get_flag("no-config") {
+        Settings::default()
+    } else {
+        load_config(matches.get_one::<String>("profile").map(String::as_str))?
+    };
+    let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    let pick = |id: &str, cfg: &Option<String>| -> Option<String> {
+        match cfg {
+            Some(v) if !from_cli(id) => Some(v.clone()),
+            _ => matches.get_one::<String>(id).cloned(),
+        }
+    };
+    // 成对的开关（--x / --no-x）：命令行给出任一形式时以命令行为准（同时给出时后者生效），否则取配置
+    let pick_flag = |on: &str, off: &str, cfg: Option<bool>, default: bool| -> bool {
+        if from_cli(on) {
+            true
+        } else if from_cli(off) {
+            false
+        } else {
+            cfg.unwrap_or(default)
+        }
+    };
+
+    match pick("color", &config.color).as_deref() {
+        Some(w) if w.eq_ignore_ascii_case("always") => colored::control::set_override(true),
+        Some(w) if w.eq_ignore_ascii_case("never") => colored::control::set_override(false),
+        _ => {}
+    }
+
+    let path = matches.get_one::<String>("path").unwrap();
+    let sort = SortSpec {
+        key: if matches.get_flag("no-sort") {
+            SortKey::None
+        } else {
+            pick("sort", &config.sort)
+                .and_then(|s| SortKey::from_arg(&s))
+                .unwrap_or_default()
+        },
+        reverse: pick_flag("reverse", "no-reverse", config.reverse, false),
+    };
+    let config_format = config.format.as_deref().map(str::to_ascii_lowercase);
+    let json_output = matches.get_flag("json")
+        || (config_format.as_deref() == Some("json") && !matches.get_flag("tree"));
+
+    let tree_mode = matches.get_flag("tree")
+        || (config_format.as_deref() == Some("tree") && !matches.get_flag("json"));
+    let recursive = matches.get_flag("recursive");
+    let follow = if matches.get_flag("follow-all") {
+        FollowSymlinks::All
+    } else if matches.get_flag("follow-cmdline") {
+        FollowSymlinks::CmdLine
+    } else {
+        matches
+            .get_one::<String>("follow-symlinks")
+            .and_then(|s| FollowSymlinks::from_arg(s))
+            .unwrap_or_default()
+    };
+
+    let summary_only = matches.get_flag("summary");
+    let size_fmt = SizeFormat {
+        units: pick("units", &config.units)
+            .and_then(|s| SizeUnits::from_arg(&s))
+            .unwrap_or_default(),
+        fixed_exp: pick("unit-fixed", &config.unit_fixed)
+            .and_then(|s| SizeFormat::fixed_exp_from_arg(&s)),
+    };
+    let render = RenderOptions {
+        size_fmt,
+        show_chart: pick_flag("chart", "no-chart", config.chart, true),
+        show_icon: pick_flag("icon", "no-icon", config.icon, false),
+        show_counts: pick_flag("counts", "no-counts", config.counts, false),
+        show_progress: progress_enabled(
+            matches.get_flag("progress"),
+            matches.get_flag("no-progress"),
+        ),
+        types: matches
+            .get_one::<TypeFilter>("type")
+            .copied()
+            .unwrap_or_default(),
+    };
+
+    if let Some(("history", sub)) = matches.subcommand() {
+        return run_history_mode(
+            sub.get_one::<String>("path").unwrap(),
+            sub.get_one::<u64>("until").copied(),
+            sub.get_flag("json"),
+            size_fmt,
+        );
+    }
+
+    let warnings = WarningTracker::new();
+    install_interrupt_handler(&warnings);
+
+    // 线程配置：命令行优先于环境变量
+    let mut thread_cfg = ThreadConfig::from_env(&warnings);
+    if let Some(&n) = matches.get_one::<u32>("threads") {
+        thread_cfg.threads = Some(n as usize);
+    }
+    if matches.get_flag("nice") {
+        thread_cfg.nice = true;
+    }
+    configure_thread_pool(thread_cfg, &warnings);
+
+    // 缓存：YP_CACHE=1 或 --cache 开启，--no-cache 优先关闭；--refresh 隐含开启
+    let cache_env = std::env::var("YP_CACHE")
+        .map(|s| matches!(s.trim(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false);
+    let cache_opts = CacheOptions {
+        enabled: !matches.get_flag("no-cache")
+            && (cache_env || matches.get_flag("cache") || matches.get_flag("refresh")),
+        refresh: matches.get_flag("refresh"),
+    };
+
+    let excludes_raw: Vec<String> = config
+        .exclude
+        .iter()
+        .flatten()
+        .cloned()
+        .chain(
+            matches
+                .get_many::<String>("exclude")
+                .into_iter()
+                .flatten()
+                .cloned(),
+        )
+        .collect();
+    let excludes = compile_excludes(excludes_raw, &warnings);
+
+    let mut budgets = Budgets {
+        max_total: matches.get_one::<u64>("max-total").copied(),
+        ..Default::default()
+    };
+    if let Some(file) = matches.get_one::<String>("budgets") {
+        budgets.load_file(Path::new(file))?;
+    }
+
+    if matches.get_flag("broken-links") {
+        return run_broken_links_mode(path, follow, json_output, render, &excludes, &warnings);
+    }
+
+    if matches.get_flag("duplicates") {
+        return run_duplicates_mode(path, follow, json_output, render, &excludes, &warnings);
+    }
+
+    if matches.get_flag("empty") {
+        return run_empty_mode(path, follow, json_output, render, &excludes, &warnings);
+    }
+
+    if matches.get_flag("reclaimable") {
+        return run_reclaimable_mode(path, follow, json_output, render, &excludes, &warnings);
+    }
+
+    if let Some(scale) = matches
+        .get_one::<String>("histogram")
+        .and_then(|s| HistogramScale::from_arg(s))
+    {
+        return run_histogram_mode(
+            path,
+            scale,
+            follow,
+            json_output,
+            render,
+            &excludes,
+            &warnings,
+        );
+    }
+
+    if let Some(&secs) = matches.get_one::<u64>("watch") {
+        return run_watch_mode(
+            path,
+            Duration::from_secs(secs),
+            tree_mode,
+            recursive,
+            follow,
+            sort,
+            render,
+            &excludes,
+            &warnings,
+        );
+    }
+
+    let record = matches.get_one::<usize>("record").copied();
+    if tree_mode {
+        return run_tree_mode(
+            path, recursive, follow, sort, render, cache_opts, &budgets, record, &excludes,
+            &warnings,
+        );
+    }
+
+    run_report_mode(
+        path,
+        recursive,
+        follow,
+        sort,
+        json_output,
+        summary_only,
+        render,
+        cache_opts,
+        &budgets,
+        record,
+        &excludes,
+        &warnings,
+    )
+}
@@ -0,0 +1,210 @@
+//! 配置文件：默认选项与命名 profile（`--profile`、`--no-config`）。
+
+use crate::cache::env_path;
+use crate::cli::{CliError, CliResult};
+use crate::format::{SizeFormat, SizeUnits};
+use crate::i18n::trf;
+use crate::sort::SortKey;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// ---- 配置层次 ----
+//
+// 依次读取全局配置与项目配置，后者覆盖前者；再叠加 --profile 选中的 profile；
+// 命令行显式给出的参数最后生效。exclude 是累加的（全局 + 项目 + profile + -e），
+// 其余键后者覆盖前者。未知键与非法取值直接报错，避免拼写错误被静默忽略。
+//
+// 全局：YP_CONFIG > $XDG_CONFIG_HOME/yp/config.toml > ~/.config/yp/config.toml > %APPDATA%\yp\config.toml
+// 项目：从当前目录向上查找的第一个 .yp.toml
+
+/// 项目配置文件名
+pub(crate) const LOCAL_CONFIG_NAME: &str = ".yp.toml";
+
+/// 配置项；顶层与 `[profiles.<name>]` 表使用相同的键
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Settings {
+    pub(crate) exclude: Option<Vec<String>>,
+    pub(crate) units: Option<String>,
+    pub(crate) unit_fixed: Option<String>,
+    pub(crate) sort: Option<String>,
+    pub(crate) reverse: Option<bool>,
+    pub(crate) chart: Option<bool>,
+    pub(crate) icon: Option<bool>,
+    pub(crate) counts: Option<bool>,
+    /// auto / always / never
+    pub(crate) color: Option<String>,
+    /// text / json / tree
+    pub(crate) format: Option<String>,
+    profiles: Option<BTreeMap<String, Settings>>,
+}
+
+impl Settings {
+    /// 以 `top` 覆盖 `self`；exclude 累加
+    fn overlay(self, top: Settings) -> Settings {
+        let exclude = match (self.exclude, top.exclude) {
+            (Some(mut a), Some(b)) => {
+                a.extend(b);
+                Some(a)
+            }
+            (a, b) => b.or(a),
+        };
+        Settings {
+            exclude,
+            units: top.units.or(self.units),
+            unit_fixed: top.unit_fixed.or(self.unit_fixed),
+            sort: top.sort.or(self.sort),
+            reverse: top.reverse.or(self.reverse),
+            chart: top.chart.or(self.chart),
+            icon: top.icon.or(self.icon),
+            counts: top.counts.or(self.counts),
+            color: top.color.or(self.color),
+            format: top.format.or(self.format),
+            profiles: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        fn check(
+            key: &str,
+            value: &Option<String>,
+            ok: impl Fn(&str) -> bool,
+        ) -> Result<(), String> {
+            match value {
+                Some(v) if !ok(v) => Err(trf("{} 的取值 '{}' 无效", &[&key, v])),
+                _ => Ok(()),
+            }
+        }
+        check("units", &self.units, |v| SizeUnits::from_arg(v).is_some())?;
+        check("unit-fixed", &self.unit_fixed, |v| {
+            SizeFormat::fixed_exp_from_arg(v).is_some()
+        })?;
+        check("sort", &self.sort, |v| SortKey::from_arg(v).is_some())?;
+        check("color", &self.color, |v| {
+            matches!(v.to_ascii_lowercase().as_str(), "auto" | "always" | "never")
+        })?;
+        check("format", &self.format, |v| {
+            matches!(v.to_ascii_lowercase().as_str(), "text" | "json" | "tree")
+        })
+    }
+}
+
+fn read_settings(path: &Path) -> CliResult<Settings> {
+    let err = |msg: String| CliError::Config {
+        path: path.to_path_buf(),
+        msg,
+    };
+    let text = fs::read_to_string(path).map_err(|e| err(e.to_string()))?;
+    let settings: Settings = toml::from_str(&text).map_err(|e| err(e.message().to_string()))?;
+    settings.validate().map_err(&err)?;
+    for (name, p) in settings.profiles.iter().flatten() {
+        if p.profiles.is_some() {
+            return Err(err(trf("profile '{}' 内不能再定义 profiles", &[name])));
+        }
+        p.validate()
+            .map_err(|m| err(format!("profile '{}': {}", name, m)))?;
+    }
+    Ok(settings)
+}
+
+pub(crate) fn global_config_file() -> Option<PathBuf> {
+    if let Some(p) = env_path("YP_CONFIG") {
+        return Some(p);
+    }
+    let base = env_path("XDG_CONFIG_HOME")
+        .or_else(|| env_path("HOME").map(|h| h.join(".config")))
+        .or_else(|| env_path("APPDATA"))?;
+    Some(base.join("yp").join("config.toml"))
+}
+
+/// 从 `dir` 向上查找项目配置
+pub(crate) fn find_local_config(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(LOCAL_CONFIG_NAME))
+        .find(|p| p.is_file())
+}
+
+/// 按顺序叠加 `files`（不存在的跳过），再叠加 `profile`
+pub(crate) fn load_layers(files: &[PathBuf], profile: Option<&str>) -> CliResult<Settings> {
+    let mut merged = Settings::default();
+    let mut profiles = BTreeMap::new();
+    for file in files.iter().filter(|f| f.is_file()) {
+        let mut s = read_settings(file)?;
+        // 同名 profile 以后读到的（更靠近项目的）为准
+        profiles.extend(s.profiles.take().unwrap_or_default());
+        merged = merged.overlay(s);
+    }
+    if let Some(name) = profile {
+        let p = profiles
+            .remove(name)
+            .ok_or_else(|| CliError::UnknownProfile(name.to_string()))?;
+        merged = merged.overlay(p);
+    }
+    Ok(merged)
+}
+
+/// 读取全局与项目配置
+pub(crate) fn load_config(profile: Option<&str>) -> CliResult<Settings> {
+    let local = std::env::current_dir()
+        .ok()
+        .and_then(|d| find_local_config(&d));
+    let files: Vec<PathBuf> = global_config_file().into_iter().chain(local).collect();
+    load_layers(&files, profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDirGuard;
+
+    #[test]
+    fn test_config_layers_and_profiles() {
+        let tmp = TempDirGuard::new("yp_config");
+        let global = tmp.path().join("config.toml");
+        let project = tmp.path().join("proj");
+        fs::create_dir_all(project.join("sub")).expect("create proj");
+        let local = project.join(LOCAL_CONFIG_NAME);
+        fs::write(
+            &global,
+            "exclude = [\".git\"]\nunits = \"si\"\nchart = false\n\
+             [profiles.ci]\nformat = \"json\"\nunits = \"bytes\"\n",
+        )
+        .expect("write global");
+        fs::write(
+            &local,
+            "exclude = [\"target\"]\nsort = \"name\"\n[profiles.deep]\nformat = \"tree\"\n",
+        )
+        .expect("write local");
+
+        assert_eq!(find_local_config(&project.join("sub")), Some(local.clone()));
+        let files = [global.clone(), local.clone()];
+        let s = load_layers(&files, None).expect("load without profile");
+        assert_eq!(s.exclude, Some(vec![".git".into(), "target".into()]));
+        assert_eq!(
+            (s.units.as_deref(), s.sort.as_deref(), s.chart),
+            (Some("si"), Some("name"), Some(false))
+        );
+        // 全局文件中定义的 profile 在项目目录下同样可用
+        let ci = load_layers(&files, Some("ci")).expect("load ci");
+        assert_eq!(
+            (ci.units.as_deref(), ci.format.as_deref()),
+            (Some("bytes"), Some("json"))
+        );
+        assert!(matches!(
+            load_layers(&files, Some("nope")),
+            Err(CliError::UnknownProfile(_))
+        ));
+
+        fs::write(&local, "sotr = \"name\"\n").expect("write typo");
+        assert!(matches!(
+            load_layers(&files, None),
+            Err(CliError::Config { .. })
+        ));
+        fs::write(&local, "units = \"furlongs\"\n").expect("write bad value");
+        assert!(matches!(
+            load_layers(&files, None),
+            Err(CliError::Config { .. })
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+//! 重复文件查找（`--duplicates`）。
+
+use crate::error::AppResult;
+use crate::exclude::ExcludeSet;
+use crate::scan::{EntryKind, ScanHooks, visit_directory};
+use crate::visit::{VisitEntry, Visitor};
+use crate::walk::file_key;
+use crate::warnings::{CTX_READ_FILE, WarningTracker};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// ---- 重复文件 ----
+//
+// 三级筛选，每一级只处理上一级仍有同伴的文件：
+//   1. 遍历时按大小分组（复用 Visitor 遍历，不建扫描树）；
+//   2. 同大小的文件先比较开头 PARTIAL_LEN 字节的哈希；
+//   3. 仍相同的再比较全文哈希（不超过 PARTIAL_LEN 的文件第 2 步即为全文）。
+// 每级内部按文件并行读取。同一 inode 的多个硬链接只保留路径最小的一个，
+// 不计为重复；空文件不参与比较。
+
+/// 部分哈希读取的字节数
+const PARTIAL_LEN: u64 = 16 * 1024;
+
+/// 一组内容完全相同的文件
+#[derive(Debug, Clone)]
+pub(crate) struct DuplicateSet {
+    /// 单个文件的大小
+    pub(crate) size: u64,
+    /// 内容的 BLAKE3 哈希（十六进制）
+    pub(crate) hash: String,
+    /// 按路径排序，至少两项
+    pub(crate) paths: Vec<PathBuf>,
+}
+
+impl DuplicateSet {
+    /// 只保留一份时可回收的字节数
+    pub(crate) fn wasted(&self) -> u64 {
+        self.size * (self.paths.len() as u64 - 1)
+    }
+}
+
+#[derive(Default)]
+struct SizeCollector {
+    by_size: Mutex<HashMap<u64, Vec<PathBuf>>>,
+}
+
+impl Visitor for SizeCollector {
+    fn entry(&self, e: &VisitEntry<'_>) {
+        // 经跟随的链接与其目标是同一个文件，本就不算重复
+        if e.kind != EntryKind::File || e.size == 0 {
+            return;
+        }
+        self.by_size
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(e.size)
+            .or_default()
+            .push(e.path.to_path_buf());
+    }
+}
+
+/// 计算文件前 `limit` 字节（None 为全文）的哈希
+fn hash_file(path: &Path, limit: Option<u64>) -> io::Result<blake3::Hash> {
+    let file = File::open(path)?;
+    let mut hasher = blake3::Hasher::new();
+    match limit {
+        Some(n) => hasher.update_reader(file.take(n))?,
+        None => hasher.update_reader(file)?,
+    };
+    Ok(hasher.finalize())
+}
+
+/// 对每组候选并行计算哈希，按 (大小, 哈希) 重新分组，只保留仍有同伴的组。
+/// 读取失败的文件告警后剔除；中断后不再读取新文件。
+fn refine(
+    groups: Vec<(u64, Vec<PathBuf>)>,
+    limit: Option<u64>,
+    warnings: &WarningTracker,
+    cancel: Option<&AtomicBool>,
+) -> Vec<(u64, blake3::Hash, Vec<PathBuf>)> {
+    let hashed: Vec<(u64, blake3::Hash, PathBuf)> = groups
+        .into_iter()
+        .flat_map(|(size, paths)| paths.into_iter().map(move |p| (size, p)))
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .filter_map(|(size, path)| {
+            if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                return None;
+            }
+            match hash_file(&path, limit) {
+                Ok(h) => Some((size, h, path)),
+                Err(e) => {
+                    warnings.warn_io(CTX_READ_FILE, &path, &e);
+                    None
+                }
+            }
+        })
+        .collect();
+
+    let mut by_hash: HashMap<(u64, blake3::Hash), Vec<PathBuf>> = HashMap::new();
+    for (size, h, path) in hashed {
+        by_hash.entry((size, h)).or_default().push(path);
+    }
+    by_hash
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|((size, h), paths)| (size, h, paths))
+        .collect()
+}
+
+/// 列出 `root` 下的全部重复文件组（按可回收字节数降序），以及扫描是否被中断。
+pub(crate) fn find_duplicates(
+    root: &Path,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+    hooks: ScanHooks,
+) -> AppResult<(Vec<DuplicateSet>, bool)> {
+    let collector = SizeCollector::default();
+    visit_directory(
+        root,
+        excludes,
+        warnings,
+        ScanHooks {
+            visitor: Some(&collector),
+            ..hooks
+        },
+    )?;
+    let by_size = collector
+        .by_size
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner());
+
+    // 同大小的候选按 inode 去重：硬链接共享同一份数据，不是浪费
+    let candidates: Vec<(u64, Vec<PathBuf>)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .map(|(size, mut paths)| {
+            paths.sort();
+            let mut seen = HashSet::new();
+            paths.retain(|p| file_key(p).is_none_or(|k| seen.insert(k)));
+            (size, paths)
+        })
+        .filter(|(_, paths)| paths.len() > 1)
+        .collect();
+
+    let partial = refine(candidates, Some(PARTIAL_LEN), warnings, hooks.cancel);
+    let (small, large): (Vec<_>, Vec<_>) = partial
+        .into_iter()
+        .partition(|(size, _, _)| *size <= PARTIAL_LEN);
+    let full = refine(
+        large
+            .into_iter()
+            .map(|(size, _, paths)| (size, paths))
+            .collect(),
+        None,
+        warnings,
+        hooks.cancel,
+    );
+
+    let mut sets: Vec<DuplicateSet> = small
+        .into_iter()
+        .chain(full)
+        .map(|(size, h, mut paths)| {
+            paths.sort();
+            DuplicateSet {
+                size,
+                hash: h.to_hex().to_string(),
+                paths,
+            }
+        })
+        .collect();
+    sets.sort_by(|a, b| {
+        b.wasted()
+            .cmp(&a.wasted())
+            .then_with(|| a.paths[0].cmp(&b.paths[0]))
+    });
+    let incomplete = hooks.cancel.is_some_and(|c| c.load(Ordering::Relaxed));
+    Ok((sets, incomplete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Fixture;
+
+    #[test]
+    fn test_refine_regroups_by_hash() {
+        let fx = Fixture::new("yp_duplicates_refine");
+        let a = fx.file("a", b"same");
+        let b = fx.file("b", b"same");
+        let c = fx.file("c", b"diff");
+        let gone = fx.path("gone");
+        let mut got = refine(
+            vec![(4, vec![a.clone(), b.clone(), c, gone])],
+            None,
+            &fx.warnings,
+            None,
+        );
+        // 只剩有同伴的一组；读取失败的文件告警后剔除
+        assert_eq!(got.len(), 1);
+        got[0].2.sort();
+        assert_eq!((got[0].1, &got[0].2), (blake3::hash(b"same"), &vec![a, b]));
+        assert_eq!(fx.warnings.warning_io(), 1);
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let fx = Fixture::new("yp_duplicates");
+        // 小文件：一组三份，另有一个同大小但内容不同的文件
+        let a = fx.file("a", b"hello");
+        let b = fx.file("b", b"hello");
+        let c = fx.file("sub/c", b"hello");
+        fx.file("sub/d", b"world");
+        // 大文件：开头相同、结尾不同的不算重复
+        let mut big = vec![7u8; PARTIAL_LEN as usize + 10];
+        let big1 = fx.file("big1", &big);
+        let big2 = fx.file("sub/big2", &big);
+        *big.last_mut().unwrap() = 8;
+        fx.file("sub/big3", &big);
+        // 空文件不参与
+        fx.file("e1", b"");
+        fx.file("e2", b"");
+        #[cfg(unix)]
+        std::fs::hard_link(&big1, fx.path("sub/big1_link")).expect("hard link");
+
+        let (sets, incomplete) =
+            find_duplicates(fx.root(), &fx.excludes, &fx.warnings, ScanHooks::default())
+                .expect("find_duplicates should succeed");
+        assert!(!incomplete);
+        assert_eq!(sets.len(), 2);
+        // 按浪费排序：大文件组在前，硬链接不额外计入
+        assert_eq!(sets[0].paths, [big1, big2]);
+        assert_eq!(sets[0].wasted(), PARTIAL_LEN + 10);
+        assert_eq!(sets[1].paths, [a, b, c]);
+        assert_eq!(sets[1].wasted(), 10);
+        assert_eq!(sets[1].hash, blake3::hash(b"hello").to_hex().as_str());
+    }
+}
@@ -0,0 +1,186 @@
+//! 空目录与零字节文件（`--empty`）。
+
+use crate::error::AppResult;
+use crate::exclude::ExcludeSet;
+use crate::scan::{DirStats, EntryKind, ScanHooks, visit_directory};
+use crate::visit::{VisitEntry, Visitor};
+use crate::warnings::WarningTracker;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// ---- 空目录 ----
+//
+// 目录在 exit_dir 时判定：子树内没有任何文件，且磁盘上的每个直接子项都是已判定为空的目录。
+// 直接子项用 read_dir 重新计数，因此只含被排除条目的目录、或含无法读取子目录的目录不算空
+// （排除规则只影响列出与遍历，不会让一个实际非空的目录被当作可删除）。
+// 子目录总在父目录之前退出，判定结果沿 parent 向上累计即可，无需建树。
+// 嵌套的空目录只报告最外层一个，并附带其下空子目录的数量。
+
+/// 一个（递归意义上）空的目录
+#[derive(Debug, Clone)]
+pub(crate) struct EmptyDir {
+    pub(crate) path: PathBuf,
+    /// 其下嵌套的空子目录数
+    pub(crate) nested: u64,
+}
+
+/// 同一父目录下的空目录与零字节文件（均按路径排序）
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EmptyGroup {
+    pub(crate) parent: PathBuf,
+    pub(crate) dirs: Vec<EmptyDir>,
+    pub(crate) files: Vec<PathBuf>,
+}
+
+struct EmptyCollector<'a> {
+    cancel: Option<&'a AtomicBool>,
+    /// 目录 -> 已判定为空的直接子目录数
+    empty_children: Mutex<HashMap<PathBuf, u64>>,
+    /// 已判定为空的目录 -> 嵌套空子目录数
+    empty_dirs: Mutex<HashMap<PathBuf, u64>>,
+    zero_files: Mutex<Vec<PathBuf>>,
+}
+
+fn lock<T>(m: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    m.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+impl Visitor for EmptyCollector<'_> {
+    fn entry(&self, e: &VisitEntry<'_>) {
+        if e.kind == EntryKind::File && e.size == 0 {
+            lock(&self.zero_files).push(e.path.to_path_buf());
+        }
+    }
+
+    fn exit_dir(&self, path: &Path, totals: DirStats) {
+        let empty_children = lock(&self.empty_children).remove(path).unwrap_or(0);
+        // 中断后的目录只列出了一部分，不作判定
+        if totals.files != 0 || self.cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            return;
+        }
+        let on_disk = match fs::read_dir(path) {
+            Ok(rd) => rd.count() as u64,
+            Err(_) => return,
+        };
+        if on_disk != empty_children {
+            return;
+        }
+        lock(&self.empty_dirs).insert(path.to_path_buf(), totals.dirs);
+        if let Some(parent) = path.parent() {
+            *lock(&self.empty_children)
+                .entry(parent.to_path_buf())
+                .or_default() += 1;
+        }
+    }
+}
+
+/// 列出 `root` 下的空目录（只取最外层）与零字节文件，按父目录分组；以及扫描是否被中断。
+pub(crate) fn find_empty(
+    root: &Path,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+    hooks: ScanHooks,
+) -> AppResult<(Vec<EmptyGroup>, bool)> {
+    let collector = EmptyCollector {
+        cancel: hooks.cancel,
+        empty_children: Mutex::default(),
+        empty_dirs: Mutex::default(),
+        zero_files: Mutex::default(),
+    };
+    visit_directory(
+        root,
+        excludes,
+        warnings,
+        ScanHooks {
+            visitor: Some(&collector),
+            ..hooks
+        },
+    )?;
+    let incomplete = hooks.cancel.is_some_and(|c| c.load(Ordering::Relaxed));
+    let empty_dirs = collector
+        .empty_dirs
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner());
+    let zero_files = collector
+        .zero_files
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner());
+
+    let parent_of = |p: &Path| p.parent().unwrap_or(p).to_path_buf();
+    let mut groups: BTreeMap<PathBuf, EmptyGroup> = BTreeMap::new();
+    for (path, nested) in &empty_dirs {
+        // 父目录本身也为空时由父目录代表（扫描根之外的父目录不在表中）
+        if path.parent().is_some_and(|p| empty_dirs.contains_key(p)) {
+            continue;
+        }
+        let parent = parent_of(path);
+        groups
+            .entry(parent.clone())
+            .or_insert_with(|| EmptyGroup {
+                parent,
+                ..Default::default()
+            })
+            .dirs
+            .push(EmptyDir {
+                path: path.clone(),
+                nested: *nested,
+            });
+    }
+    for path in zero_files {
+        let parent = parent_of(&path);
+        groups
+            .entry(parent.clone())
+            .or_insert_with(|| EmptyGroup {
+                parent,
+                ..Default::default()
+            })
+            .files
+            .push(path);
+    }
+    let groups = groups
+        .into_values()
+        .map(|mut g| {
+            g.dirs.sort_by(|a, b| a.path.cmp(&b.path));
+            g.files.sort();
+            g
+        })
+        .collect();
+    Ok((groups, incomplete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Fixture;
+
+    #[test]
+    fn test_find_empty() {
+        // 只含被排除文件的目录不算空
+        let fx = Fixture::with_excludes("yp_empty", &["skip.log"]);
+        fx.file("e/skip.log", b"");
+        // a/b/c 全空：只报告 a，嵌套 2 个
+        fx.dir("a/b/c");
+        // x 含非空文件，其下的空目录 y 与零字节文件 z 单独报告
+        let y = fx.dir("x/y");
+        fx.file("x/data", b"1");
+        let z = fx.file("x/z", b"");
+
+        let root = fx.root();
+        let (groups, incomplete) =
+            find_empty(root, &fx.excludes, &fx.warnings, ScanHooks::default()).expect("find_empty");
+        assert!(!incomplete);
+        assert_eq!(groups.len(), 2);
+
+        assert_eq!(groups[0].parent, root);
+        let dirs: Vec<_> = groups[0].dirs.iter().map(|d| (&d.path, d.nested)).collect();
+        assert_eq!(dirs, [(&fx.path("a"), 2)]);
+        assert!(groups[0].files.is_empty());
+
+        assert_eq!(groups[1].parent, fx.path("x"));
+        assert_eq!(groups[1].dirs[0].path, y);
+        assert_eq!(groups[1].files, [z]);
+    }
+}
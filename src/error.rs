@@ -0,0 +1,53 @@
+//! 错误类型。
+
+use crate::i18n::trf;
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+// ---- AppError ----
+//
+// [FIX-BUG-3] 删除从未使用的 UnsupportedFileType 变体。
+/// 扫描失败的原因。新增变体不视为破坏性变更，匹配时请保留通配分支。
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum AppError {
+    /// 扫描根路径不存在
+    #[error("{}", trf("路径不存在: {}", &[&.0.display()]))]
+    PathNotFound(PathBuf),
+
+    /// 根目录无法读取（子目录读取失败只记为告警）
+    #[error("{}", trf("无法读取目录: {} ({})", &[&.path.display(), .source]))]
+    ReadDir {
+        /// 出错的目录
+        path: PathBuf,
+        /// 底层 IO 错误
+        #[source]
+        source: io::Error,
+    },
+
+    /// 根路径的元数据无法读取
+    #[error("{}", trf("无法读取元数据: {} ({})", &[&.path.display(), .source]))]
+    Metadata {
+        /// 出错的路径
+        path: PathBuf,
+        /// 底层 IO 错误
+        #[source]
+        source: io::Error,
+    },
+
+    /// JSON 序列化或输出失败
+    #[error("{}", trf("JSON 序列化错误: {}", &[.0]))]
+    Json(#[from] serde_json::Error),
+
+    /// 条目数超出扫描树的编号范围（约 42 亿项）
+    #[error("{}", trf("条目过多，超出扫描树的编号范围（{} 项）", &[.0]))]
+    TooManyEntries(usize),
+
+    /// 指定线程数的工作线程池创建失败
+    #[error("{}", trf("无法创建线程池: {}", &[.0]))]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+}
+
+/// `Result<T, AppError>` 的简写。
+pub type AppResult<T> = Result<T, AppError>;
@@ -0,0 +1,315 @@
+//! 排除规则（`-e`）的编译与匹配。
+
+use crate::i18n::tr;
+use crate::scan::ScanContext;
+use crate::warnings::WarningTracker;
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+// ---- CWD 缓存（词法绝对化，不 canonicalize） ----
+pub(crate) static CWD: OnceLock<PathBuf> = OnceLock::new();
+pub(crate) fn cwd() -> &'static PathBuf {
+    CWD.get_or_init(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")))
+}
+
+/// 仅做词法层去除 '.'（CurDir）组件；不处理 '..'（保持"字面路径"策略）。
+pub(crate) fn normalize_curdir_only(p: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for comp in p.components() {
+        if comp != Component::CurDir {
+            out.push(comp.as_os_str());
+        }
+    }
+    out
+}
+
+/// 将路径提升到同一坐标系（可注入 cwd，便于测试）：
+/// - 绝对路径：仅去掉 '.' 组件
+/// - 相对路径：cwd join 后去掉 '.' 组件
+///   不做 '..' 归一化。
+pub(crate) fn absify_for_compare_with_cwd(p: &Path, cwd: &Path) -> PathBuf {
+    if p.is_absolute() {
+        normalize_curdir_only(p)
+    } else {
+        normalize_curdir_only(&cwd.join(p))
+    }
+}
+
+/// 生产路径使用全局 CWD。
+#[inline]
+pub(crate) fn absify_for_compare(p: &Path) -> PathBuf {
+    absify_for_compare_with_cwd(p, cwd())
+}
+
+// ---- ExcludePattern ----
+//
+// [FIX-BUG-1] 删除 Rel 变体。
+// compile_excludes 阶段统一将路径类模式提升为 Abs（使用 absify_for_compare），
+// 从而保证 entry.path()（绝对路径）与排除模式始终在同一坐标系下比较。
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum ExcludePattern {
+    /// 仅匹配文件名（不含路径分隔符），使用 `OsString` 以支持非 UTF-8 名称
+    Name(OsString),
+    /// 绝对化后的路径，与 absify_for_compare(entry.path()) 直接比较
+    Abs(PathBuf),
+}
+
+// ---- ExcludeSet ----
+//
+// [FIX-MAINT-6] 为了在 should_exclude 的热路径避免重复扫描 patterns，
+// 保留 `has_abs` 作为只读缓存字段（在构造时设定，之后不可变）。
+#[derive(Debug, Clone)]
+pub(crate) struct ExcludeSet {
+    pub(crate) patterns: Vec<ExcludePattern>,
+    /// 缓存是否存在 Abs 模式，避免在热路径重复扫描 patterns。
+    pub(crate) has_abs: bool,
+}
+
+impl ExcludeSet {
+    /// 是否存在 Abs 模式（用于 should_exclude 热路径决策是否执行 absify）
+    #[inline]
+    pub(crate) fn has_abs(&self) -> bool {
+        self.has_abs
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// 排除规则指纹，用于判断持久缓存是否在相同规则下生成。
+    /// 指纹写入磁盘，因此用 blake3 而非 DefaultHasher（后者的算法随 Rust 版本可能变化）；
+    /// 与规则的书写顺序无关。
+    pub(crate) fn fingerprint(&self) -> u64 {
+        let mut encoded: Vec<Vec<u8>> = self
+            .patterns
+            .iter()
+            .map(|p| {
+                let (tag, s) = match p {
+                    ExcludePattern::Name(n) => (b'n', n.as_os_str()),
+                    ExcludePattern::Abs(a) => (b'a', a.as_os_str()),
+                };
+                let bytes = os_bytes(s);
+                // 类型 + 长度前缀，避免不同规则拼接后产生相同的字节串
+                let mut e = Vec::with_capacity(bytes.len() + 9);
+                e.push(tag);
+                e.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+                e.extend_from_slice(&bytes);
+                e
+            })
+            .collect();
+        encoded.sort();
+        encoded.dedup();
+        let mut h = blake3::Hasher::new();
+        for e in &encoded {
+            h.update(e);
+        }
+        let digest = h.finalize();
+        let mut first = [0u8; 8];
+        first.copy_from_slice(&digest.as_bytes()[..8]);
+        u64::from_le_bytes(first)
+    }
+}
+
+/// 名称/路径的原始字节；非 Unix 平台取 UTF-8 有损形式
+#[cfg(unix)]
+fn os_bytes(s: &OsStr) -> std::borrow::Cow<'_, [u8]> {
+    use std::os::unix::ffi::OsStrExt;
+    std::borrow::Cow::Borrowed(s.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn os_bytes(s: &OsStr) -> std::borrow::Cow<'_, [u8]> {
+    match s.to_string_lossy() {
+        std::borrow::Cow::Borrowed(b) => std::borrow::Cow::Borrowed(b.as_bytes()),
+        std::borrow::Cow::Owned(o) => std::borrow::Cow::Owned(o.into_bytes()),
+    }
+}
+
+// ---- compile_excludes ----
+//
+// [FIX-BUG-1] Rel 模式在此处统一提升为 Abs，使用 absify_for_compare。
+// 这样 should_exclude 只需比较两个绝对路径，无需关心 root 坐标系。
+pub(crate) fn compile_excludes(raw: Vec<String>, warnings: &WarningTracker) -> ExcludeSet {
+    let mut patterns = Vec::new();
+
+    for p in raw {
+        if p.trim().is_empty() {
+            warnings.warn_msg(tr("忽略空的 exclude 模式（-e \"\" 或仅空白）"));
+            continue;
+        }
+
+        let is_path_like = p.contains('/') || (cfg!(windows) && p.contains('\\'));
+        if is_path_like {
+            // 路径类模式：无论相对/绝对，统一提升为 absify_for_compare 坐标系
+            let abs = absify_for_compare(Path::new(&p));
+            patterns.push(ExcludePattern::Abs(abs));
+        } else {
+            patterns.push(ExcludePattern::Name(OsString::from(p)));
+        }
+    }
+
+    let has_abs = patterns.iter().any(|p| matches!(p, ExcludePattern::Abs(_)));
+    ExcludeSet { patterns, has_abs }
+}
+
+// ---- should_exclude ----
+//
+// [FIX-PERF-4] 接收预计算的 root_abs（来自 ScanContext），避免热路径重复 normalize。
+// [FIX-BUG-1] Rel 变体已删除，所有路径模式均为 Abs，统一 absify 后比较。
+pub(crate) fn should_exclude(current_path: &Path, ctx: &ScanContext) -> bool {
+    if ctx.excludes.is_empty() {
+        return false;
+    }
+
+    let name_os = current_path.file_name();
+
+    // 第一遍：仅检查 Name 模式（无需分配）
+    for pat in &ctx.excludes.patterns {
+        match pat {
+            ExcludePattern::Name(n) if name_os == Some(n.as_os_str()) => return true,
+            _ => {}
+        }
+    }
+
+    // 第二遍：若存在 Abs 模式，才执行 absify（可能分配）。
+    // 优化：当 current_path 已是绝对路径时，仅做最小化的 normalize；
+    // 否则以 ctx.root_abs 作为基准避免反复查询全局 CWD。
+    if ctx.excludes.has_abs() {
+        let cur_abs = if current_path.is_absolute() {
+            normalize_curdir_only(current_path)
+        } else {
+            absify_for_compare_with_cwd(current_path, ctx.root_abs.as_path())
+        };
+
+        for pat in &ctx.excludes.patterns {
+            match pat {
+                ExcludePattern::Abs(pat_abs) if cur_abs == *pat_abs => return true,
+                _ => {}
+            }
+        }
+    }
+
+    false
+}
+
+/// 判断 `dir` 下名为 `name` 的条目是否排除：Name 模式直接比较条目名，
+/// 只有存在 Abs 模式时才拼出完整路径交给 should_exclude。
+pub(crate) fn should_exclude_child(dir: &Path, name: &OsStr, ctx: &ScanContext) -> bool {
+    if ctx.excludes.is_empty() {
+        return false;
+    }
+    if ctx.excludes.has_abs() {
+        return should_exclude(&dir.join(name), ctx);
+    }
+    ctx.excludes
+        .patterns
+        .iter()
+        .any(|pat| matches!(pat, ExcludePattern::Name(n) if n.as_os_str() == name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // [FIX-TEST-7] 使用可注入 cwd 的纯函数，不依赖全局 CWD
+    #[test]
+    fn test_abs_exclude_dot_slash_path() {
+        let fake_cwd = Path::new("/home/user/project");
+
+        // 模拟 compile_excludes 对 "foo/bar" 的处理
+        let abs_pat = absify_for_compare_with_cwd(Path::new("foo/bar"), fake_cwd);
+        let excludes = ExcludeSet {
+            patterns: vec![ExcludePattern::Abs(abs_pat)],
+            has_abs: true,
+        };
+
+        // entry.path() 通常返回绝对路径
+        let entry_path = Path::new("/home/user/project/foo/bar");
+        let abs_entry = absify_for_compare_with_cwd(entry_path, fake_cwd);
+
+        assert!(
+            excludes
+                .patterns
+                .iter()
+                .any(|p| matches!(p, ExcludePattern::Abs(a) if *a == abs_entry)),
+            "绝对路径应匹配排除模式"
+        );
+    }
+
+    #[test]
+    fn test_abs_exclude_dot_slash_prefix() {
+        let fake_cwd = Path::new("/home/user/project");
+
+        // 用户输入 "./foo/bar"
+        let abs_pat = absify_for_compare_with_cwd(Path::new("./foo/bar"), fake_cwd);
+        // entry.path() 返回 /home/user/project/foo/bar
+        let abs_entry =
+            absify_for_compare_with_cwd(Path::new("/home/user/project/foo/bar"), fake_cwd);
+
+        assert_eq!(
+            abs_pat, abs_entry,
+            "'./foo/bar' 与 'foo/bar' 应绝对化为同一路径"
+        );
+    }
+
+    #[test]
+    fn test_name_exclude_no_alloc_path() {
+        // Name 模式下，has_abs() 返回 false，不触发 absify
+        let excludes = ExcludeSet {
+            patterns: vec![ExcludePattern::Name(OsString::from("node_modules"))],
+            has_abs: false,
+        };
+        assert!(!excludes.has_abs());
+    }
+
+    #[test]
+    fn test_has_abs_derived_from_patterns() {
+        let excludes_no_abs = ExcludeSet {
+            patterns: vec![ExcludePattern::Name(OsString::from("foo"))],
+            has_abs: false,
+        };
+        assert!(!excludes_no_abs.has_abs());
+
+        let excludes_with_abs = ExcludeSet {
+            patterns: vec![ExcludePattern::Abs(PathBuf::from("/some/path"))],
+            has_abs: true,
+        };
+        assert!(excludes_with_abs.has_abs());
+    }
+
+    #[test]
+    fn test_fingerprint_stable_and_order_independent() {
+        let a = ExcludePattern::Name(OsString::from("node_modules"));
+        let b = ExcludePattern::Abs(PathBuf::from("/srv/data"));
+        let set = |patterns: Vec<ExcludePattern>| ExcludeSet {
+            has_abs: patterns.iter().any(|p| matches!(p, ExcludePattern::Abs(_))),
+            patterns,
+        };
+        let ab = set(vec![a.clone(), b.clone()]).fingerprint();
+        assert_eq!(ab, set(vec![b.clone(), a.clone()]).fingerprint());
+        assert_ne!(ab, set(vec![a]).fingerprint());
+        // 同一字符串作为名称与路径规则时含义不同
+        assert_ne!(
+            set(vec![ExcludePattern::Name(OsString::from("x"))]).fingerprint(),
+            set(vec![ExcludePattern::Abs(PathBuf::from("x"))]).fingerprint()
+        );
+        // 指纹写入缓存文件，值不能随工具链变化
+        assert_eq!(ab, 17_098_098_875_578_396_322);
+    }
+
+    #[test]
+    fn test_normalize_curdir_only() {
+        let p = Path::new("./foo/./bar");
+        let norm = normalize_curdir_only(p);
+        assert_eq!(norm, PathBuf::from("foo/bar"));
+    }
+
+    #[test]
+    fn test_normalize_preserves_dotdot() {
+        // '..' 不应被处理
+        let p = Path::new("foo/../bar");
+        let norm = normalize_curdir_only(p);
+        assert_eq!(norm, PathBuf::from("foo/../bar"));
+    }
+}
@@ -0,0 +1,70 @@
+//! 列表过滤：按条目类型（`--type`）。
+
+use crate::i18n::{tr, trf};
+use crate::scan::EntryKind;
+
+// ---- TypeFilter ----
+//
+// 只影响"列出哪些条目"，不影响统计：总大小与目录的子树统计仍包含被过滤掉的条目。
+
+/// 允许列出的条目类型集合；默认全部允许。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TypeFilter {
+    mask: u16,
+}
+
+impl Default for TypeFilter {
+    fn default() -> Self {
+        Self { mask: u16::MAX }
+    }
+}
+
+impl TypeFilter {
+    fn bit(kind: EntryKind) -> u16 {
+        1 << kind as u16
+    }
+
+    pub(crate) fn matches(&self, kind: EntryKind) -> bool {
+        self.mask & Self::bit(kind) != 0
+    }
+
+    /// 解析 `f,d,l` 形式的列表；也接受 JSON 中的完整名称（file、dir、symlink……）。
+    pub(crate) fn parse(s: &str) -> Result<Self, String> {
+        let mut mask = 0;
+        for part in s.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let kind = match part {
+                "f" => EntryKind::File,
+                "d" => EntryKind::Dir,
+                "l" => EntryKind::Symlink,
+                "s" => EntryKind::Socket,
+                "p" => EntryKind::Fifo,
+                "c" => EntryKind::CharDevice,
+                "b" => EntryKind::BlockDevice,
+                other => EntryKind::from_name(other)
+                    .ok_or_else(|| trf("未知类型 '{}'（可用：f、d、l、s、p、c、b）", &[&other]))?,
+            };
+            mask |= Self::bit(kind);
+        }
+        if mask == 0 {
+            return Err(tr("类型列表为空").to_string());
+        }
+        Ok(Self { mask })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_filter_parse() {
+        let f = TypeFilter::parse("f, l,socket").expect("valid filter");
+        assert!(f.matches(EntryKind::File));
+        assert!(f.matches(EntryKind::Symlink));
+        assert!(f.matches(EntryKind::Socket));
+        assert!(!f.matches(EntryKind::Dir));
+        assert!(TypeFilter::default().matches(EntryKind::BlockDevice));
+        assert!(TypeFilter::parse("x").is_err());
+        assert!(TypeFilter::parse(",").is_err());
+    }
+}
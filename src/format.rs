@@ -0,0 +1,328 @@
+//! 大小格式化、文件名截断与终端宽度。
+
+use std::borrow::Cow;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use terminal_size::{Width, terminal_size};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// ---- suffix 截断防退化参数 ----
+pub(crate) const ZW_BASE: usize = 8;
+pub(crate) const ZW_PER_VISIBLE: usize = 8;
+pub(crate) const UTF8_MAX_BYTES: usize = 4;
+pub(crate) const SUFFIX_BYTE_BUDGET_PAD: usize = 32;
+
+pub(crate) fn suffix_byte_budget(limit: usize) -> usize {
+    limit
+        .saturating_mul(UTF8_MAX_BYTES)
+        .saturating_add(SUFFIX_BYTE_BUDGET_PAD)
+}
+
+// ---- 格式化 ----
+
+pub(crate) const IEC_UNITS: [&str; 7] = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+pub(crate) const SI_UNITS: [&str; 7] = ["B", "kB", "MB", "GB", "TB", "PB", "EB"];
+
+/// 大小单位体系（`--units`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum SizeUnits {
+    /// 二进制单位（KiB/MiB/…，基数 1024）
+    #[default]
+    Iec,
+    /// 十进制单位（kB/MB/…，基数 1000）
+    Si,
+    /// 精确字节数，不换算
+    Bytes,
+}
+
+impl SizeUnits {
+    pub(crate) fn from_arg(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "iec" => Some(Self::Iec),
+            "si" => Some(Self::Si),
+            "bytes" => Some(Self::Bytes),
+            _ => None,
+        }
+    }
+}
+
+/// 大小格式化配置：单位体系 + 可选的固定单位（`--unit-fixed`）。
+/// `fixed_exp` 为 1..=4，分别对应 K/M/G/T；`Bytes` 模式下忽略固定单位。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct SizeFormat {
+    pub(crate) units: SizeUnits,
+    pub(crate) fixed_exp: Option<u32>,
+}
+
+impl SizeFormat {
+    pub(crate) fn fixed_exp_from_arg(s: &str) -> Option<u32> {
+        match s.to_ascii_uppercase().as_str() {
+            "K" => Some(1),
+            "M" => Some(2),
+            "G" => Some(3),
+            "T" => Some(4),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn format(&self, size: u64) -> String {
+        let (base, names): (u128, &[&str; 7]) = match self.units {
+            SizeUnits::Bytes => return format!("{} B", size),
+            SizeUnits::Iec => (1024, &IEC_UNITS),
+            SizeUnits::Si => (1000, &SI_UNITS),
+        };
+        let n = size as u128;
+
+        // 固定单位：所有行使用同一单位，便于纵向对齐比较
+        if let Some(exp) = self.fixed_exp {
+            let divisor = base.pow(exp);
+            let value100 = (n * 100 + divisor / 2) / divisor;
+            return format!(
+                "{}.{:02} {}",
+                value100 / 100,
+                value100 % 100,
+                names[exp as usize]
+            );
+        }
+
+        if n < base {
+            return format!("{} {}", size, names[0]);
+        }
+
+        let mut divisor: u128 = base;
+        for (unit, unit_name) in names.iter().enumerate().skip(1) {
+            let value100 = (n * 100 + divisor / 2) / divisor;
+            if value100 < base * 100 || unit + 1 == names.len() {
+                return format!("{}.{:02} {}", value100 / 100, value100 % 100, unit_name);
+            }
+            divisor *= base;
+        }
+
+        unreachable!("SizeFormat::format: exhausted units for size={}", size)
+    }
+}
+
+pub(crate) fn lossy_display<T>(value: &T) -> Cow<'_, str>
+where
+    T: AsRef<OsStr> + ?Sized,
+{
+    value.as_ref().to_string_lossy()
+}
+
+pub(crate) fn app_error_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+pub(crate) fn prefix_end_by_width(s: &str, limit: usize) -> usize {
+    let mut w = 0usize;
+    let mut end = 0usize;
+    for (i, ch) in s.char_indices() {
+        let cw = ch.width().unwrap_or(0);
+        if w + cw > limit {
+            break;
+        }
+        w += cw;
+        end = i + ch.len_utf8();
+    }
+    end
+}
+
+pub(crate) fn suffix_start_index_by_width(s: &str, limit: usize) -> usize {
+    if limit == 0 {
+        return s.len();
+    }
+
+    let byte_budget = suffix_byte_budget(limit);
+
+    let mut acc = 0usize;
+    let mut visible = 0usize;
+    let mut zw_used = 0usize;
+    let mut bytes_used = 0usize;
+
+    s.char_indices()
+        .rev()
+        .take_while(|(_, ch)| {
+            let cw = ch.width().unwrap_or(0);
+            let clen = ch.len_utf8();
+
+            if bytes_used + clen > byte_budget {
+                return false;
+            }
+
+            if cw == 0 {
+                if acc >= limit {
+                    return false;
+                }
+                let zw_budget = ZW_BASE + visible.saturating_mul(ZW_PER_VISIBLE);
+                if zw_used + 1 > zw_budget {
+                    return false;
+                }
+                zw_used += 1;
+                bytes_used += clen;
+                true
+            } else if acc + cw <= limit {
+                acc += cw;
+                visible += 1;
+                bytes_used += clen;
+                true
+            } else {
+                false
+            }
+        })
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(s.len())
+}
+
+pub(crate) fn truncate_filename(name: &str, max_width: usize) -> String {
+    if name.width() <= max_width {
+        return name.to_string();
+    }
+    if max_width <= 3 {
+        return "...".to_string();
+    }
+
+    let available = max_width - 3;
+    let left = available / 2;
+    let right = available - left;
+
+    let prefix_end = prefix_end_by_width(name, left);
+    let mut suffix_start = suffix_start_index_by_width(name, right);
+    if suffix_start < prefix_end {
+        suffix_start = prefix_end;
+    }
+
+    let mut result = String::new();
+    result.push_str(&name[..prefix_end]);
+    result.push_str("...");
+    result.push_str(&name[suffix_start..]);
+    result
+}
+
+/// 解析 `500M`、`1.5G`、`4096` 形式的大小。单字母与 `KiB` 等为 1024 进制，
+/// `KB`、`MB` 等为 1000 进制，与 `--units` 的两种显示方式对应；不区分大小写。
+pub(crate) fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let value: f64 = num.parse().ok()?;
+    let mult: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kib" => 1 << 10,
+        "m" | "mib" => 1 << 20,
+        "g" | "gib" => 1 << 30,
+        "t" | "tib" => 1 << 40,
+        "kb" => 1_000,
+        "mb" => 1_000_000,
+        "gb" => 1_000_000_000,
+        "tb" => 1_000_000_000_000,
+        _ => return None,
+    };
+    let bytes = (value * mult as f64).round();
+    (bytes.is_finite() && bytes <= u64::MAX as f64).then_some(bytes as u64)
+}
+
+/// Unix 时间（秒）格式化为 UTC 的 `YYYY-MM-DD HH:MM`（civil_from_days 算法，不依赖时区库）
+pub(crate) fn format_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        y,
+        m,
+        d,
+        rem / 3_600,
+        rem % 3_600 / 60
+    )
+}
+
+pub(crate) fn get_terminal_width() -> usize {
+    if let Some((Width(w), _)) = terminal_size() {
+        (w as usize).clamp(60, 160)
+    } else {
+        100
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_size_boundaries() {
+        let fmt = SizeFormat::default();
+        assert_eq!(fmt.format(0), "0 B");
+        assert_eq!(fmt.format(1023), "1023 B");
+        assert_eq!(fmt.format(1024), "1.00 KiB");
+        assert_eq!(fmt.format(1024 * 1024), "1.00 MiB");
+        assert_eq!(fmt.format(u64::MAX), "16.00 EiB");
+    }
+
+    #[test]
+    fn test_format_size_si_bytes_and_fixed() {
+        let si = SizeFormat {
+            units: SizeUnits::Si,
+            fixed_exp: None,
+        };
+        assert_eq!(si.format(999), "999 B");
+        assert_eq!(si.format(1000), "1.00 kB");
+        assert_eq!(si.format(1_500_000), "1.50 MB");
+
+        let bytes = SizeFormat {
+            units: SizeUnits::Bytes,
+            fixed_exp: Some(2),
+        };
+        assert_eq!(bytes.format(123_456_789), "123456789 B");
+
+        let fixed_m = SizeFormat {
+            units: SizeUnits::Iec,
+            fixed_exp: SizeFormat::fixed_exp_from_arg("m"),
+        };
+        assert_eq!(fixed_m.format(0), "0.00 MiB");
+        assert_eq!(fixed_m.format(3 * 1024 * 1024 * 1024), "3072.00 MiB");
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("4096"), Some(4096));
+        assert_eq!(parse_size("500M"), Some(500 << 20));
+        assert_eq!(parse_size("1.5g"), Some(3 << 29));
+        assert_eq!(parse_size("2 KiB"), Some(2048));
+        assert_eq!(parse_size("5MB"), Some(5_000_000));
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("M"), None);
+        assert_eq!(parse_size("5X"), None);
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00");
+        assert_eq!(format_utc(951_782_400), "2000-02-29 00:00");
+        assert_eq!(format_utc(1_700_000_000), "2023-11-14 22:13");
+    }
+
+    #[test]
+    fn test_truncate_filename_ascii() {
+        let s = "hello_world_long_name.txt";
+        let t = truncate_filename(s, 10);
+        assert!(t.width() <= 10, "截断后宽度应 <= 10，实际：{}", t.width());
+        assert!(t.contains("..."), "应包含省略号");
+    }
+
+    #[test]
+    fn test_truncate_filename_no_truncate_needed() {
+        let s = "short.txt";
+        assert_eq!(truncate_filename(s, 20), "short.txt");
+    }
+}
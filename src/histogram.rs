@@ -0,0 +1,206 @@
+//! 文件大小分布（`--histogram`）。
+
+use crate::error::AppResult;
+use crate::exclude::ExcludeSet;
+use crate::scan::{EntryKind, ScanHooks, visit_directory};
+use crate::visit::{VisitEntry, Visitor};
+use crate::warnings::WarningTracker;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// ---- 分桶 ----
+//
+// 第 0 桶只含空文件；其余第 k 桶为 [base^(k-1), base^k)，base 为 2 或 10。
+// u64 最多 64 个二进制位 / 20 个十进制位，因此 65 个桶足以覆盖两种刻度。
+// 只统计普通文件（不含目录、symlink 与特殊文件）；回调在多个 worker 上并发，计数用原子量。
+
+const MAX_BUCKETS: usize = 65;
+
+/// 桶的刻度
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum HistogramScale {
+    /// 2 的幂
+    #[default]
+    Pow2,
+    /// 10 的幂
+    Decade,
+}
+
+impl HistogramScale {
+    pub(crate) fn from_arg(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "pow2" => Some(Self::Pow2),
+            "decade" => Some(Self::Decade),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Pow2 => "pow2",
+            Self::Decade => "decade",
+        }
+    }
+
+    fn index(self, size: u64) -> usize {
+        match self {
+            Self::Pow2 => (u64::BITS - size.leading_zeros()) as usize,
+            Self::Decade => size.checked_ilog10().map_or(0, |d| d as usize + 1),
+        }
+    }
+
+    /// 第 `idx` 桶的下界（含）与上界（不含；超出 u64 时为 None）
+    fn bounds(self, idx: usize) -> (u64, Option<u64>) {
+        if idx == 0 {
+            return (0, Some(1));
+        }
+        let base: u64 = match self {
+            Self::Pow2 => 2,
+            Self::Decade => 10,
+        };
+        let lo = base.pow(idx as u32 - 1);
+        (lo, base.checked_pow(idx as u32))
+    }
+}
+
+/// 一个大小区间内的文件数与字节数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Bucket {
+    /// 下界（含）
+    pub(crate) min: u64,
+    /// 上界（不含）；最后一个桶可能没有上界
+    pub(crate) max: Option<u64>,
+    pub(crate) files: u64,
+    pub(crate) bytes: u64,
+}
+
+/// 从最小到最大的非空桶之间的连续区间（中间的空桶保留，便于看出分布形状）
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Histogram {
+    pub(crate) scale: HistogramScale,
+    pub(crate) buckets: Vec<Bucket>,
+}
+
+impl Histogram {
+    pub(crate) fn total_files(&self) -> u64 {
+        self.buckets.iter().map(|b| b.files).sum()
+    }
+
+    pub(crate) fn total_bytes(&self) -> u64 {
+        self.buckets.iter().map(|b| b.bytes).sum()
+    }
+}
+
+struct HistogramCollector {
+    scale: HistogramScale,
+    files: [AtomicU64; MAX_BUCKETS],
+    bytes: [AtomicU64; MAX_BUCKETS],
+}
+
+impl Visitor for HistogramCollector {
+    fn entry(&self, e: &VisitEntry<'_>) {
+        if e.kind != EntryKind::File {
+            return;
+        }
+        let idx = self.scale.index(e.size);
+        self.files[idx].fetch_add(1, Ordering::Relaxed);
+        self.bytes[idx].fetch_add(e.size, Ordering::Relaxed);
+    }
+}
+
+/// 统计 `root` 下普通文件的大小分布，以及扫描是否被中断。
+pub(crate) fn build_histogram(
+    root: &Path,
+    scale: HistogramScale,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+    hooks: ScanHooks,
+) -> AppResult<(Histogram, bool)> {
+    let collector = HistogramCollector {
+        scale,
+        files: std::array::from_fn(|_| AtomicU64::new(0)),
+        bytes: std::array::from_fn(|_| AtomicU64::new(0)),
+    };
+    visit_directory(
+        root,
+        excludes,
+        warnings,
+        ScanHooks {
+            visitor: Some(&collector),
+            ..hooks
+        },
+    )?;
+    let incomplete = hooks.cancel.is_some_and(|c| c.load(Ordering::Relaxed));
+
+    let files: Vec<u64> = collector
+        .files
+        .iter()
+        .map(|a| a.load(Ordering::Relaxed))
+        .collect();
+    let used = files
+        .iter()
+        .position(|&n| n > 0)
+        .zip(files.iter().rposition(|&n| n > 0));
+    let buckets = match used {
+        Some((first, last)) => (first..=last)
+            .map(|idx| {
+                let (min, max) = scale.bounds(idx);
+                Bucket {
+                    min,
+                    max,
+                    files: files[idx],
+                    bytes: collector.bytes[idx].load(Ordering::Relaxed),
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    Ok((Histogram { scale, buckets }, incomplete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Fixture;
+
+    #[test]
+    fn test_histogram_buckets() {
+        assert_eq!(HistogramScale::Pow2.index(0), 0);
+        assert_eq!(HistogramScale::Pow2.index(1), 1);
+        assert_eq!(HistogramScale::Pow2.index(1023), 10);
+        assert_eq!(HistogramScale::Pow2.index(1024), 11);
+        assert_eq!(HistogramScale::Pow2.bounds(11), (1024, Some(2048)));
+        assert_eq!(HistogramScale::Pow2.bounds(64), (1 << 63, None));
+        assert_eq!(HistogramScale::Decade.index(9), 1);
+        assert_eq!(HistogramScale::Decade.index(10), 2);
+        assert_eq!(HistogramScale::Decade.bounds(2), (10, Some(100)));
+        assert_eq!(HistogramScale::Decade.index(u64::MAX), 20);
+        assert_eq!(HistogramScale::Decade.bounds(20).1, None);
+    }
+
+    #[test]
+    fn test_build_histogram() {
+        let fx = Fixture::new("yp_histogram");
+        fx.file("a", [0u8; 3]);
+        fx.file("sub/b", [0u8; 2]);
+        fx.file("c", [0u8; 20]);
+
+        let (h, incomplete) = build_histogram(
+            fx.root(),
+            HistogramScale::Pow2,
+            &fx.excludes,
+            &fx.warnings,
+            ScanHooks::default(),
+        )
+        .expect("build_histogram should succeed");
+        assert!(!incomplete);
+        // [2,4) 两个文件，[4,8) 与 [8,16) 为空，[16,32) 一个
+        let rows: Vec<_> = h
+            .buckets
+            .iter()
+            .map(|b| (b.min, b.files, b.bytes))
+            .collect();
+        assert_eq!(rows, [(2, 2, 5), (4, 0, 0), (8, 0, 0), (16, 1, 20)]);
+        assert_eq!((h.total_files(), h.total_bytes()), (3, 25));
+    }
+}
@@ -0,0 +1,283 @@
+//! 扫描历史（`--record` 与 `yp history`）：追加式记录各目录总量，查询大小变化趋势。
+
+use crate::cache::env_path;
+use crate::exclude::absify_for_compare;
+use crate::scan::DirStats;
+use crate::visit::Visitor;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// ---- 历史文件 ----
+//
+// JSON Lines，每次 --record 追加一行，从不改写已有内容：多个定时任务并发追加也不会互相覆盖
+// （单次 write 一整行，O_APPEND 保证不交错）。无法解析的行（被截断的最后一行等）读取时跳过。
+// 扫描根与查询路径都先 canonicalize，使 `.`、相对路径与经符号链接的写法落到同一条记录上。
+
+/// 一次扫描的记录
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct HistoryRecord {
+    /// Unix 时间（秒）
+    pub(crate) time: u64,
+    /// 扫描根（绝对路径）
+    pub(crate) root: String,
+    pub(crate) depth: usize,
+    /// 扫描根的总大小
+    pub(crate) total: u64,
+    /// 深度不超过 depth 的各目录（相对扫描根）及其子树大小
+    pub(crate) dirs: Vec<(String, u64)>,
+}
+
+impl HistoryRecord {
+    /// `path`（绝对路径）在这次扫描中的大小；不在记录范围内时为 None
+    fn size_of(&self, path: &Path) -> Option<u64> {
+        let rel = path.strip_prefix(&self.root).ok()?;
+        if rel.as_os_str().is_empty() {
+            return Some(self.total);
+        }
+        let rel = rel.to_string_lossy();
+        self.dirs.iter().find(|(p, _)| *p == rel).map(|&(_, s)| s)
+    }
+}
+
+/// 历史文件位置：YP_HISTORY_FILE > $XDG_DATA_HOME/yp > ~/.local/share/yp > %LOCALAPPDATA%\\yp
+pub(crate) fn default_history_file() -> Option<PathBuf> {
+    if let Some(p) = env_path("YP_HISTORY_FILE") {
+        return Some(p);
+    }
+    let base = env_path("XDG_DATA_HOME")
+        .or_else(|| env_path("HOME").map(|h| h.join(".local").join("share")))
+        .or_else(|| env_path("LOCALAPPDATA"))?;
+    Some(base.join("yp").join("history.jsonl"))
+}
+
+/// 用于记录与查询的统一路径形式
+pub(crate) fn history_key(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| absify_for_compare(path))
+}
+
+pub(crate) fn append_record(file: &Path, rec: &HistoryRecord) -> io::Result<()> {
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut line = serde_json::to_vec(rec).map_err(io::Error::other)?;
+    line.push(b'\n');
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file)?
+        .write_all(&line)
+}
+
+/// 读取 `path` 的历史大小 (时间, 字节)，按时间排序
+pub(crate) fn load_history(file: &Path, path: &Path) -> io::Result<Vec<(u64, u64)>> {
+    let text = match fs::read_to_string(file) {
+        Ok(t) => t,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut points: Vec<(u64, u64)> = text
+        .lines()
+        .filter_map(|l| serde_json::from_str::<HistoryRecord>(l).ok())
+        .filter_map(|r| r.size_of(path).map(|s| (r.time, s)))
+        .collect();
+    points.sort_by_key(|&(t, _)| t);
+    Ok(points)
+}
+
+// ---- 记录 ----
+
+/// 收集深度不超过 `depth` 的各目录总量；挂在主扫描的 `ScanHooks.visitor` 上，不另做遍历
+pub(crate) struct DepthCollector<'a> {
+    root: &'a Path,
+    depth: usize,
+    dirs: Mutex<Vec<(String, u64)>>,
+}
+
+impl<'a> DepthCollector<'a> {
+    pub(crate) fn new(root: &'a Path, depth: usize) -> Self {
+        Self {
+            root,
+            depth,
+            dirs: Mutex::default(),
+        }
+    }
+
+    /// 扫描结束后生成记录；`total` 为同一次扫描的总大小
+    pub(crate) fn into_record(self, total: u64) -> HistoryRecord {
+        // 遍历使用调用方给出的路径（排除规则、告警与其他模式一致），记录时再换成绝对路径
+        let key = history_key(self.root);
+        let mut dirs = self.dirs.into_inner().unwrap_or_else(|e| e.into_inner());
+        dirs.sort();
+        HistoryRecord {
+            time: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            root: key.to_string_lossy().into_owned(),
+            depth: self.depth,
+            total,
+            dirs,
+        }
+    }
+}
+
+impl Visitor for DepthCollector<'_> {
+    fn exit_dir(&self, path: &Path, totals: DirStats) {
+        let Ok(rel) = path.strip_prefix(self.root) else {
+            return;
+        };
+        let level = rel.components().count();
+        if level == 0 || level > self.depth {
+            return;
+        }
+        self.dirs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((rel.to_string_lossy().into_owned(), totals.size));
+    }
+}
+
+// ---- 趋势 ----
+
+/// 最小二乘拟合的增长速率（字节/秒）；少于两个不同时间点时为 None
+pub(crate) fn growth_rate(points: &[(u64, u64)]) -> Option<f64> {
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return None;
+    }
+    let t0 = points[0].0 as f64;
+    let mean_t = points.iter().map(|&(t, _)| t as f64 - t0).sum::<f64>() / n;
+    let mean_s = points.iter().map(|&(_, s)| s as f64).sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for &(t, s) in points {
+        let dt = t as f64 - t0 - mean_t;
+        cov += dt * (s as f64 - mean_s);
+        var += dt * dt;
+    }
+    (var > 0.0).then(|| cov / var)
+}
+
+/// 达到目标大小的预计
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Projection {
+    /// 最近一次记录已不小于目标
+    Reached,
+    /// 没有增长（或数据不足），不会达到
+    Never,
+    /// 预计在该 Unix 时间达到
+    At(u64),
+}
+
+/// 按拟合速率从最近一次记录外推到 `target`
+pub(crate) fn project(points: &[(u64, u64)], target: u64) -> Projection {
+    let Some(&(last_t, last_s)) = points.last() else {
+        return Projection::Never;
+    };
+    if last_s >= target {
+        return Projection::Reached;
+    }
+    match growth_rate(points) {
+        Some(rate) if rate > 0.0 => {
+            // 速率极小时所需秒数超出 u64（as 会饱和），视为不会达到
+            let secs = ((target - last_s) as f64 / rate).ceil();
+            if secs < u64::MAX as f64 {
+                last_t
+                    .checked_add(secs as u64)
+                    .map_or(Projection::Never, Projection::At)
+            } else {
+                Projection::Never
+            }
+        }
+        _ => Projection::Never,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan::{ScanHooks, analyze_directory};
+    use crate::test_util::Fixture;
+
+    #[test]
+    fn test_record_and_load_history() {
+        let fx = Fixture::new("yp_history");
+        fx.file("data/a/deep/er/f", [0u8; 7]);
+        fx.file("data/top", [0u8; 3]);
+        let root = fx.path("data");
+
+        // 与 CLI 相同：挂在非 recursive 的主扫描上
+        let collector = DepthCollector::new(&root, 2);
+        let report = analyze_directory(
+            &root,
+            false,
+            &fx.excludes,
+            &fx.warnings,
+            ScanHooks {
+                visitor: Some(&collector),
+                ..Default::default()
+            },
+        )
+        .expect("scan should succeed");
+        let mut rec = collector.into_record(report.total_size);
+        assert_eq!(rec.total, 10);
+        let sep = std::path::MAIN_SEPARATOR;
+        assert_eq!(
+            rec.dirs,
+            [("a".to_string(), 7), (format!("a{}deep", sep), 7)]
+        );
+
+        let file = fx.path("hist/history.jsonl");
+        rec.time = 1_000;
+        append_record(&file, &rec).expect("append 1");
+        rec.time = 1_000 + 86_400;
+        rec.total = 20;
+        rec.dirs[0].1 = 17;
+        append_record(&file, &rec).expect("append 2");
+        // 截断的行被跳过
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&file)
+            .and_then(|mut f| f.write_all(b"{\"time\":"))
+            .expect("append garbage");
+
+        let key = history_key(&root);
+        assert_eq!(
+            load_history(&file, &key).expect("load root"),
+            [(1_000, 10), (87_400, 20)]
+        );
+        assert_eq!(
+            load_history(&file, &key.join("a")).expect("load a"),
+            [(1_000, 7), (87_400, 17)]
+        );
+        // 超出记录深度的目录没有历史
+        let deeper = key.join("a").join("deep").join("er");
+        assert!(
+            load_history(&file, &deeper)
+                .expect("load deeper")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_growth_projection() {
+        let day = 86_400;
+        let points = [(0, 100), (day, 200), (2 * day, 300)];
+        let rate = growth_rate(&points).expect("rate");
+        assert!((rate * day as f64 - 100.0).abs() < 1e-6);
+        assert_eq!(project(&points, 500), Projection::At(4 * day));
+        assert_eq!(project(&points, 300), Projection::Reached);
+        assert_eq!(project(&[(0, 5), (day, 5)], 10), Projection::Never);
+        assert_eq!(growth_rate(&[(0, 5)]), None);
+        // 斜率接近 0 但为正：到达时间无法表示，不能溢出
+        let slow = [(0, 0), (u64::MAX / 2, 1)];
+        assert!(growth_rate(&slow).is_some_and(|r| r > 0.0));
+        assert_eq!(project(&slow, u64::MAX), Projection::Never);
+        assert_eq!(
+            project(&[(day, 0), (2 * day, 1)], u64::MAX),
+            Projection::Never
+        );
+    }
+}
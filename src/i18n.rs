@@ -0,0 +1,679 @@
+//! 界面语言：中文原文即消息 ID，英文由 `EN` 目录提供。
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// ---- 语言选择 ----
+//
+// 与 gettext 相同，源码里的中文原文就是消息 ID：tr("目录:") 在中文下原样返回，
+// 在英文下查 EN 目录；目录缺项时退回原文而不是报错。带参数的消息用 `{}` 占位，
+// 由 trf 按顺序填入，英文译文须保留相同个数的占位符（由测试检查）。
+// 随数量变化的消息用 trn，英文单复数两种形式登记在 EN_PLURAL；中文不区分单复数。
+// 含两个数量的句子拆成两条各自取形式后再拼接。
+//
+// 语言在启动时确定一次：--lang > LC_ALL > LC_MESSAGES > LANG。
+// 取值以 zh 开头为中文，其余（含 C/POSIX）为英文；均未设置时保持中文。
+
+/// 输出语言
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Lang {
+    /// 简体中文（默认）
+    #[default]
+    Zh,
+    /// 英文
+    En,
+}
+
+impl Lang {
+    pub(crate) fn from_arg(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "zh" => Some(Self::Zh),
+            "en" => Some(Self::En),
+            _ => None,
+        }
+    }
+
+    /// 由 locale 字符串（如 `zh_CN.UTF-8`、`en_US`、`C`）判断语言
+    pub(crate) fn from_locale(s: &str) -> Self {
+        if s.to_ascii_lowercase().starts_with("zh") {
+            Self::Zh
+        } else {
+            Self::En
+        }
+    }
+
+    /// 按 LC_ALL > LC_MESSAGES > LANG 的顺序取第一个非空的 locale
+    pub(crate) fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|k| std::env::var(k).ok())
+            .find(|v| !v.is_empty())
+            .map_or(Self::Zh, |v| Self::from_locale(&v))
+    }
+}
+
+static LANG: AtomicU8 = AtomicU8::new(0);
+
+/// 设置之后所有输出、告警与错误信息使用的语言（进程级）。
+pub fn set_lang(lang: Lang) {
+    LANG.store(
+        match lang {
+            Lang::Zh => 0,
+            Lang::En => 1,
+        },
+        Ordering::Relaxed,
+    );
+}
+
+pub(crate) fn lang() -> Lang {
+    match LANG.load(Ordering::Relaxed) {
+        1 => Lang::En,
+        _ => Lang::Zh,
+    }
+}
+
+/// `id` 在 `lang` 下的文本
+pub(crate) fn lookup(lang: Lang, id: &'static str) -> &'static str {
+    static EN_MAP: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    match lang {
+        Lang::Zh => id,
+        Lang::En => EN_MAP
+            .get_or_init(|| EN.iter().copied().collect())
+            .get(id)
+            .copied()
+            .unwrap_or(id),
+    }
+}
+
+/// 按当前语言翻译
+pub(crate) fn tr(id: &'static str) -> &'static str {
+    lookup(lang(), id)
+}
+
+/// 按当前语言翻译并依次填入 `{}` 占位符
+pub(crate) fn trf(id: &'static str, args: &[&dyn Display]) -> String {
+    fill(tr(id), args)
+}
+
+/// `id` 在 `lang` 下按数量 `n` 选取的单复数形式
+pub(crate) fn lookup_n(lang: Lang, id: &'static str, n: u64) -> &'static str {
+    static EN_PLURAL_MAP: OnceLock<HashMap<&'static str, (&'static str, &'static str)>> =
+        OnceLock::new();
+    match lang {
+        Lang::Zh => id,
+        Lang::En => EN_PLURAL_MAP
+            .get_or_init(|| {
+                EN_PLURAL
+                    .iter()
+                    .map(|&(id, one, other)| (id, (one, other)))
+                    .collect()
+            })
+            .get(id)
+            .map_or(id, |&(one, other)| if n == 1 { one } else { other }),
+    }
+}
+
+/// 同 trf，但按数量 `n` 选择单复数（`n` 通常也是第一个参数）
+pub(crate) fn trn(id: &'static str, n: u64, args: &[&dyn Display]) -> String {
+    fill(lookup_n(lang(), id, n), args)
+}
+
+fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len() + 16);
+    let mut args = args.iter();
+    let mut rest = template;
+    while let Some(i) = rest.find("{}") {
+        out.push_str(&rest[..i]);
+        if let Some(a) = args.next() {
+            out.push_str(&a.to_string());
+        }
+        rest = &rest[i + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+// ---- 英文目录 ----
+
+static EN: &[(&str, &str)] = &[
+    // budget.rs
+    (
+        "缺少 '<='（格式：<路径或模式> <= <大小> [each]）",
+        "missing '<=' (format: <path or pattern> <= <size> [each])",
+    ),
+    ("缺少大小", "missing size"),
+    ("缺少路径或模式", "missing path or pattern"),
+    (
+        "each 只能用于通配模式",
+        "each can only be used with glob patterns",
+    ),
+    ("第 {} 行: {}", "line {}: {}"),
+    ("无效的大小 '{}'", "invalid size '{}'"),
+    ("无法识别 '{}'", "unrecognized '{}'"),
+    (
+        "预算规则的路径不存在或已被排除: {}",
+        "budget rule path does not exist or is excluded: {}",
+    ),
+    // cache.rs
+    (
+        "无法确定缓存目录（未设置 HOME/XDG_CACHE_HOME），本次不使用缓存",
+        "cannot determine the cache directory (HOME/XDG_CACHE_HOME not set); cache disabled for this run",
+    ),
+    // cli.rs
+    (
+        "无法确定历史文件位置（未设置 HOME/XDG_DATA_HOME），本次不记录",
+        "cannot determine the history file location (HOME/XDG_DATA_HOME not set); not recording",
+    ),
+    ("错误:", "error:"),
+    ("YP - 目录空间查看器", "YP - directory space viewer"),
+    ("指定要分析的目录路径", "Directory to analyze"),
+    (
+        "排序方式：size（大小降序，默认）、name（名称自然序）、count（文件数降序）、\nmtime（修改时间，最新在前）、ext（扩展名）、none（不排序）。\n对文本、tree 与 JSON 输出一致生效",
+        "Sort order: size (largest first, default), name (natural order), count (most files first),\nmtime (newest first), ext (extension), none (unsorted).\nApplies to text, tree and JSON output alike",
+    ),
+    ("反转排序结果", "Reverse the sort order"),
+    (
+        "不反转排序结果（覆盖配置文件中的 reverse）",
+        "Do not reverse the sort order (overrides reverse in the config file)",
+    ),
+    (
+        "禁用排序，等同于 --sort none",
+        "Disable sorting, same as --sort none",
+    ),
+    ("以 JSON 格式输出", "Output as JSON"),
+    (
+        "禁用 ASCII 条形图（默认启用）",
+        "Disable the ASCII bar chart (enabled by default)",
+    ),
+    (
+        "显示 ASCII 条形图（覆盖配置文件中的 chart）",
+        "Show the ASCII bar chart (overrides chart in the config file)",
+    ),
+    (
+        "递归显示所有子目录（tree 模式下展开所有层级；默认不跟随符号链接）",
+        "Recursively list all subdirectories (expands every level in tree mode; symlinks are not followed by default)",
+    ),
+    (
+        "以树状方式显示每个文件/目录及其大小（与 -r 结合递归展开；默认不跟随符号链接）",
+        "Show every file/directory and its size as a tree (combine with -r to expand recursively; symlinks are not followed by default)",
+    ),
+    (
+        "跟随符号链接，统计链接目标：all（遍历中的全部链接，默认）、\ncmdline（仅 -p 给出的路径本身）。按 (设备, inode) 识别循环与重复，同一目录只统计一次；\n失效链接给出警告并按链接自身计；经链接到达的条目名后标记 @",
+        "Follow symbolic links and measure their targets: all (every link met during the scan, default),\ncmdline (only the path given with -p). Cycles and duplicates are detected by (device, inode), each directory is counted once;\nbroken links produce a warning and count as the link itself; entries reached through a link are marked with @",
+    ),
+    (
+        "等同于 --follow-symlinks=all",
+        "Same as --follow-symlinks=all",
+    ),
+    (
+        "等同于 --follow-symlinks=cmdline",
+        "Same as --follow-symlinks=cmdline",
+    ),
+    (
+        "递归列出路径下所有失效（悬空）的符号链接及其指向，可与 -j、-e 组合",
+        "Recursively list every broken (dangling) symlink under the path with its target; combines with -j and -e",
+    ),
+    (
+        "递归查找内容相同的文件：先按大小分组，再依次比较部分与全文哈希；\n按可回收空间降序列出每组，同一 inode 的硬链接与空文件不计，可与 -j、-e 组合",
+        "Recursively find files with identical content: grouped by size, then compared by partial and full hashes;\nsets are listed by reclaimable space, hard links to the same inode and empty files are ignored; combines with -j and -e",
+    ),
+    (
+        "递归列出空目录（含只包含空目录的目录，只报告最外层）与零字节文件，\n按父目录分组；含被排除条目的目录不算空，可与 -j、-e 组合",
+        "Recursively list empty directories (including ones that only contain empty directories; only the outermost is reported)\nand zero-byte files, grouped by parent; directories holding excluded entries are not empty; combines with -j and -e",
+    ),
+    (
+        "递归识别可清理的缓存与构建目录：node_modules、target、.venv、__pycache__、\n.gradle、.cache、dist 与 Docker 构建缓存（按名称及 Cargo.toml、package.json\n等标记文件判断）；按类别汇总大小并列出最大的若干项及其项目根",
+        "Recursively detect cache and build directories that can be cleaned: node_modules, target, .venv, __pycache__,\n.gradle, .cache, dist and Docker build caches (identified by name and marker files such as Cargo.toml and\npackage.json); prints per-category totals and the largest matches with their project roots",
+    ),
+    (
+        "递归统计普通文件的大小分布：pow2（按 2 的幂分桶，默认）或 decade（按 10 的幂）；\n每个桶给出文件数与总字节数，条形图按文件数绘制（--no-chart 关闭）",
+        "Recursively report the size distribution of regular files: pow2 (power-of-two buckets, default) or decade (powers of ten);\neach bucket shows its file count and total bytes, the bar chart follows the file count (--no-chart to hide)",
+    ),
+    (
+        "每隔 SECS 秒（默认 2）重新扫描并原位刷新文本或 tree 视图，\n高亮自上次刷新以来增长的条目并显示增量与增长速率；Ctrl-C 退出",
+        "Rescan every SECS seconds (default 2) and redraw the text or tree view in place,\nhighlighting entries that grew since the previous refresh with the increase and growth rate; Ctrl-C to exit",
+    ),
+    (
+        "总大小上限（如 500M、2G、1.5GB）；超出时在输出后列出并以退出码 3 结束，\nJSON 输出增加 budget_violations 字段",
+        "Total size limit (e.g. 500M, 2G, 1.5GB); when exceeded, violations are listed after the output and yp exits with status 3,\nJSON output gains a budget_violations field",
+    ),
+    (
+        "预算规则文件，每行 `<路径或模式> <= <大小> [each]`，路径相对于扫描根，\n如 `dist/ <= 50M`、`assets/**/*.png <= 5M each`；违规处理同 --max-total",
+        "Budget rules file, one `<path or pattern> <= <size> [each]` per line, paths relative to the scan root,\ne.g. `dist/ <= 50M`, `assets/**/*.png <= 5M each`; violations are handled like --max-total",
+    ),
+    (
+        "扫描后把扫描根及 DEPTH 层（默认 2）以内各目录的总大小追加到历史文件\n（YP_HISTORY_FILE，默认 ~/.local/share/yp/history.jsonl），供 yp history 查看；\n数据取自同一次扫描（不使用扫描缓存），被中断的扫描不记录",
+        "After scanning, append the totals of the scan root and of every directory up to DEPTH levels (default 2) to the history file\n(YP_HISTORY_FILE, default ~/.local/share/yp/history.jsonl) for yp history;\ntotals come from the same scan (the scan cache is bypassed), interrupted scans are not recorded",
+    ),
+    (
+        "只列出指定类型的条目，逗号分隔：f 文件、d 目录、l 符号链接、s 套接字、\np 命名管道、c 字符设备、b 块设备（如 --type f,l）。\n不影响总大小与目录统计；tree 模式下目录始终保留以显示层级",
+        "Only list entries of the given kinds, comma separated: f file, d dir, l symlink, s socket,\np fifo, c char device, b block device (e.g. --type f,l).\nTotals are unaffected; in tree mode directories are always kept to show the hierarchy",
+    ),
+    (
+        "tree 模式显示图标（📁/📄）",
+        "Show icons in tree mode (📁/📄)",
+    ),
+    (
+        "不显示图标（覆盖配置文件中的 icon）",
+        "Do not show icons (overrides icon in the config file)",
+    ),
+    (
+        "显示每个目录的递归文件数/目录数（inode 占用），文本与 tree 模式均适用",
+        "Show recursive file/directory counts (inode usage) for each directory, in text and tree modes",
+    ),
+    (
+        "不显示文件数/目录数（覆盖配置文件中的 counts）",
+        "Do not show file/directory counts (overrides counts in the config file)",
+    ),
+    (
+        "扫描时在 stderr 显示进度行（默认仅当 stderr 为终端时启用）",
+        "Show a progress line on stderr while scanning (by default only when stderr is a terminal)",
+    ),
+    ("禁用进度行", "Disable the progress line"),
+    (
+        "工作线程数（默认等于 CPU 核数，也可用环境变量 YP_THREADS 设置）。\n机械盘/繁忙的 NFS 上调小可减少并发 I/O，NVMe 上可调大。\nYP_PAR_MIN_ENTRIES 仅控制子项数达到多少才交给线程池，不改变线程数",
+        "Number of worker threads (defaults to the number of CPUs, or set YP_THREADS).\nLower it on spinning disks or busy NFS to reduce concurrent I/O, raise it on NVMe.\nYP_PAR_MIN_ENTRIES only sets how many children a directory needs before it is handed to the pool, not the thread count",
+    ),
+    (
+        "低影响模式：单线程、空闲 I/O 优先级、最低 CPU 优先级（也可用 YP_NICE=1）。\n与 --threads 同时指定时以 --threads 的线程数为准",
+        "Low-impact mode: single thread, idle I/O priority, lowest CPU priority (or YP_NICE=1).\nWhen combined with --threads, the thread count from --threads wins",
+    ),
+    (
+        "启用持久扫描缓存（也可用 YP_CACHE=1）：目录 mtime 未变时沿用缓存的统计，跳过其 read_dir 与文件 stat。\n仅作用于非 -r 的列表与 tree 模式；原地改写的文件不改变目录 mtime，需 --refresh 才能反映",
+        "Enable the persistent scan cache (or YP_CACHE=1): directories whose mtime is unchanged reuse their cached totals,\nskipping read_dir and per-file stats. Only used by non -r listing and tree modes; files rewritten in place\ndo not change the directory mtime and need --refresh",
+    ),
+    (
+        "禁用持久扫描缓存（覆盖 --cache 与 YP_CACHE）",
+        "Disable the persistent scan cache (overrides --cache and YP_CACHE)",
+    ),
+    (
+        "忽略已有缓存完整扫描，并用结果重写缓存",
+        "Ignore cached entries, rescan fully and rewrite the cache",
+    ),
+    (
+        "只显示目录/总大小/项目数，不显示详细条目",
+        "Only show the directory, total size and item count, without entries",
+    ),
+    (
+        "大小单位：iec（KiB/MiB，默认）、si（kB/MB）、bytes（精确字节数）",
+        "Size units: iec (KiB/MiB, default), si (kB/MB), bytes (exact byte counts)",
+    ),
+    (
+        "所有行使用固定单位（K/M/G/T），便于对齐比较；--units bytes 时忽略",
+        "Use a fixed unit (K/M/G/T) on every line for easier comparison; ignored with --units bytes",
+    ),
+    (
+        "彩色输出：auto（默认，遵循 NO_COLOR 与终端检测）、always、never",
+        "Colour output: auto (default, honours NO_COLOR and terminal detection), always, never",
+    ),
+    (
+        "界面语言：en 或 zh（默认按 LC_ALL、LC_MESSAGES、LANG 判断，zh 开头为中文）",
+        "Interface language: en or zh (by default taken from LC_ALL, LC_MESSAGES, LANG; values starting with zh select Chinese)",
+    ),
+    (
+        "使用配置文件中 [profiles.NAME] 定义的一组选项，叠加在配置文件的默认值之上",
+        "Apply the options defined in [profiles.NAME] of the configuration file on top of its defaults",
+    ),
+    (
+        "不读取配置文件（全局 ~/.config/yp/config.toml 或 YP_CONFIG，\n及从当前目录向上找到的 .yp.toml）",
+        "Do not read configuration files (the global ~/.config/yp/config.toml or YP_CONFIG,\nand the .yp.toml found from the current directory upwards)",
+    ),
+    (
+        "排除指定文件/文件夹（可多次使用）。\n支持：\n• 名称模式（如 node_modules）：匹配任意层级同名条目\n• 路径模式（含 / 则视为路径）：统一绝对化后比较，\n相对路径以 CWD 为基准；不处理 '..' 归一化。\nsymlink 默认不跟随，size 取链接自身元数据长度。",
+        "Exclude files/folders (may be repeated).\nSupports:\n• name patterns (e.g. node_modules): match entries with that name at any depth\n• path patterns (containing /): compared after making both absolute,\nrelative paths are based on the CWD; '..' is not normalized.\nSymlinks are not followed by default; their size is the link's own metadata length.",
+    ),
+    (
+        "显示 --record 记录的目录大小变化、增长速率与达到指定大小的预计时间",
+        "Show how a directory recorded with --record changed in size, its growth rate and when it is projected to reach a given size",
+    ),
+    (
+        "要查看的目录（扫描根或其记录深度内的子目录）",
+        "Directory to show (a scan root or a subdirectory within its recorded depth)",
+    ),
+    (
+        "按线性趋势预测何时达到该大小（如 100G）",
+        "Project when the size will be reached, following the linear trend (e.g. 100G)",
+    ),
+    (
+        "无法注册 Ctrl-C 处理器，中断将直接退出 ({})",
+        "cannot register the Ctrl-C handler, interrupting will exit immediately ({})",
+    ),
+    (
+        "每 {} 秒刷新，Ctrl-C 退出",
+        "refreshing every {} s, Ctrl-C to exit",
+    ),
+    // config.rs
+    ("{} 的取值 '{}' 无效", "invalid value for {}: '{}'"),
+    (
+        "profile '{}' 内不能再定义 profiles",
+        "profile '{}' cannot define profiles of its own",
+    ),
+    // error.rs（AppError）
+    ("路径不存在: {}", "path does not exist: {}"),
+    ("无法读取目录: {} ({})", "cannot read directory: {} ({})"),
+    ("无法读取元数据: {} ({})", "cannot read metadata: {} ({})"),
+    ("JSON 序列化错误: {}", "JSON serialization error: {}"),
+    (
+        "条目过多，超出扫描树的编号范围（{} 项）",
+        "too many entries for the scan tree ({} entries)",
+    ),
+    ("无法创建线程池: {}", "cannot create thread pool: {}"),
+    // cli.rs（CliError）
+    ("预算规则文件 {}: {}", "budget rules file {}: {}"),
+    ("配置文件 {}: {}", "config file {}: {}"),
+    ("未定义的 profile: {}", "undefined profile: {}"),
+    (
+        "无法读取扫描历史: {} ({})",
+        "cannot read scan history: {} ({})",
+    ),
+    // exclude.rs
+    (
+        "忽略空的 exclude 模式（-e \"\" 或仅空白）",
+        "ignoring empty exclude pattern (-e \"\" or whitespace only)",
+    ),
+    // filter.rs
+    ("类型列表为空", "type list is empty"),
+    (
+        "未知类型 '{}'（可用：f、d、l、s、p、c、b）",
+        "unknown type '{}' (available: f, d, l, s, p, c, b)",
+    ),
+    // progress.rs
+    (
+        "扫描中 {}s | 目录 {} | 文件 {} | {} | ",
+        "scanning {}s | dirs {} | files {} | {} | ",
+    ),
+    // render.rs
+    ("注意:", "Note:"),
+    (
+        "扫描被中断（Ctrl-C），以下为不完整的部分结果",
+        "Scan interrupted (Ctrl-C); partial results below",
+    ),
+    ("目录:", "Directory:"),
+    ("总大小:", "Total size:"),
+    ("项目数:", "Count:"),
+    ("目录为空", "Directory is empty"),
+    ("{}，{}", "{}, {}"),
+    ("共计:", "Total:"),
+    ("总计:", "Total:"),
+    ("没有普通文件", "No regular files"),
+    (
+        "没有该目录的扫描记录（用 --record 记录，且目录需在记录深度内）",
+        "No scan records for this directory (record with --record; the directory must be within the recorded depth)",
+    ),
+    ("时间 (UTC)", "Time (UTC)"),
+    ("大小", "Size"),
+    ("变化", "Change"),
+    ("增长速率:", "Growth rate:"),
+    (
+        "至少需要两个不同时间的记录",
+        "needs at least two records at different times",
+    ),
+    ("预测:", "Projection:"),
+    ("超出预算:", "Over budget:"),
+    ("未发现失效的符号链接", "No broken symlinks found"),
+    ("未发现重复文件", "No duplicate files found"),
+    (
+        "未发现空目录或零字节文件",
+        "No empty directories or zero-byte files found",
+    ),
+    (
+        "未发现可回收的缓存或构建目录",
+        "No reclaimable cache or build directories found",
+    ),
+    ("按类别:", "By category:"),
+    ("路径:", "Path:"),
+    (
+        "{}/天（{} 次记录线性拟合）",
+        "{}/day (linear fit over {} records)",
+    ),
+    ("已达到 {}", "already at {}"),
+    (
+        "按当前趋势不会达到 {}",
+        "will not reach {} at the current trend",
+    ),
+    ("{} 天", "{} days"),
+    ("{} 小时", "{} hours"),
+    (
+        "预计 {} (UTC) 达到 {}（最近一次记录后约 {}）",
+        "expected on {} (UTC) to reach {} (about {} after the latest record)",
+    ),
+    ("(浪费 {})", "(wasted {})"),
+    ("最大的 {} 项:", "Largest {}:"),
+    ("(项目: {})", "(project: {})"),
+    // scan.rs
+    (
+        "指定路径是文件而非目录，--recursive 无效",
+        "the path is a file, not a directory; --recursive has no effect",
+    ),
+    // threads.rs
+    (
+        "当前平台不支持调整 I/O 优先级",
+        "adjusting I/O priority is not supported on this platform",
+    ),
+    (
+        "忽略无效的 YP_THREADS={}（需为正整数）",
+        "ignoring invalid YP_THREADS={} (must be a positive integer)",
+    ),
+    (
+        "无法降低 I/O/CPU 优先级，仅限制线程数 ({})",
+        "cannot lower I/O/CPU priority, only limiting threads ({})",
+    ),
+    (
+        "无法配置线程池，使用默认设置 ({})",
+        "cannot configure the thread pool, using defaults ({})",
+    ),
+    // walk.rs
+    (
+        "同一目录经多条路径到达",
+        "the same directory was reached through another path",
+    ),
+    (
+        "目标为上级目录或已由其他路径统计",
+        "target is a parent directory or was already counted through another path",
+    ),
+    // warnings.rs（WarningTracker）
+    ("无法读取目录", "cannot read directory"),
+    ("无法读取目录项", "cannot read directory entry"),
+    ("无法读取元数据", "cannot read metadata"),
+    ("符号链接目标不存在", "symlink target does not exist"),
+    (
+        "符号链接指向已统计的目录，未跟随",
+        "symlink points to an already counted directory, not followed",
+    ),
+    (
+        "目录已经由其他路径统计，不重复计入",
+        "directory already counted through another path, skipped",
+    ),
+    ("无法读取文件内容", "cannot read file contents"),
+    ("无法读取扫描缓存", "cannot read scan cache"),
+    ("无法写入扫描缓存", "cannot write scan cache"),
+    ("无法写入扫描历史", "cannot write scan history"),
+    ("警告:", "warning:"),
+    ("提示:", "Note:"),
+    (
+        "已达到告警上限（{} 条），后续警告将不再逐条打印。",
+        "warning limit reached ({}), further warnings will not be printed individually.",
+    ),
+    // cli.rs 的 about 文本（与 Cargo.toml 的 description 一致）
+    (
+        "一个高性能的目录空间占用查看工具\nPACI mail@pa.ci",
+        "A fast directory disk usage viewer\nPACI mail@pa.ci",
+    ),
+];
+
+/// 按数量变化的消息：(消息 ID, 单数, 复数)
+static EN_PLURAL: &[(&str, &str, &str)] = &[
+    // render.rs
+    ("{} 个项目", "{} item", "{} items"),
+    ("{} 文件", "{} file", "{} files"),
+    ("{} 目录", "{} dir", "{} dirs"),
+    (" 个文件", " file", " files"),
+    ("{} 个文件，{}", "{} file, {}", "{} files, {}"),
+    ("{} 分钟", "{} minute", "{} minutes"),
+    ("{} 条规则超限", "{} violation", "{} violations"),
+    ("{} 个失效链接", "{} broken link", "{} broken links"),
+    (
+        "{} 组重复文件，可回收 {}",
+        "{} set of duplicate files, {} reclaimable",
+        "{} sets of duplicate files, {} reclaimable",
+    ),
+    (
+        " (含 {} 个空子目录)",
+        " ({} empty subdirectory)",
+        " ({} empty subdirectories)",
+    ),
+    ("{} 个空目录", "{} empty directory", "{} empty directories"),
+    ("{} 个零字节文件", "{} zero-byte file", "{} zero-byte files"),
+    ("{} 个目录", "{} directory", "{} directories"),
+    (
+        "{} 项，可回收 {}",
+        "{} directory, {} reclaimable",
+        "{} directories, {} reclaimable",
+    ),
+    // warnings.rs
+    (
+        "本次运行产生 {} 条警告（IO: {}，参数: {}）。部分结果可能偏小或与预期不符。",
+        "{} warning in this run (IO: {}, parameters: {}). Some results may be too small or unexpected.",
+        "{} warnings in this run (IO: {}, parameters: {}). Some results may be too small or unexpected.",
+    ),
+    (
+        "本次运行产生 {} 条 IO 警告，部分结果可能偏小。",
+        "{} IO warning in this run, some results may be too small.",
+        "{} IO warnings in this run, some results may be too small.",
+    ),
+    (
+        "本轮扫描产生 {} 条警告，部分结果可能偏小",
+        "{} warning in this refresh, some results may be too small",
+        "{} warnings in this refresh, some results may be too small",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::warnings::{
+        CTX_BROKEN_LINK, CTX_CACHE_READ, CTX_CACHE_WRITE, CTX_DIR_SEEN, CTX_HISTORY_WRITE,
+        CTX_LINK_SEEN, CTX_METADATA, CTX_READ_DIR, CTX_READ_ENTRY, CTX_READ_FILE,
+    };
+
+    /// 取出源码中 `pats` 调用（如 tr("...")）的字面量（可换行书写）并还原转义（含 `\` 续行）
+    fn message_ids(src: &str, pats: &[&str]) -> Vec<String> {
+        let mut ids = Vec::new();
+        for pat in pats {
+            let mut rest = src;
+            while let Some(i) = rest.find(pat) {
+                // 排除 push_str( 之类以 tr( 结尾的其他标识符
+                let ident_tail = rest[..i]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_');
+                rest = rest[i + pat.len()..].trim_start();
+                let Some(lit) = rest.strip_prefix('"').filter(|_| !ident_tail) else {
+                    continue;
+                };
+                rest = lit;
+                let mut id = String::new();
+                let mut chars = rest.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => id.push('\n'),
+                            Some('\n') => {
+                                chars = chars.as_str().trim_start().chars();
+                            }
+                            Some(other) => id.push(other),
+                            None => break,
+                        },
+                        c => id.push(c),
+                    }
+                }
+                rest = chars.as_str();
+                ids.push(id);
+            }
+        }
+        ids
+    }
+
+    #[test]
+    fn test_catalog_complete() {
+        // 遍历 src/ 下全部模块，新增模块无需手动登记（i18n.rs 自身只含示例与测试）
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src");
+        let mut sources = Vec::new();
+        for entry in std::fs::read_dir(dir).expect("read src dir") {
+            let path = entry.expect("read src entry").path();
+            if path.extension().is_some_and(|e| e == "rs") && !path.ends_with("i18n.rs") {
+                sources.push(std::fs::read_to_string(&path).expect("read source file"));
+            }
+        }
+        assert!(sources.len() > 20, "未找到源码文件: {}", dir);
+        let en: HashMap<&str, &str> = EN.iter().copied().collect();
+        assert_eq!(en.len(), EN.len(), "EN 目录中有重复的消息 ID");
+        let mut missing = Vec::new();
+        for id in sources
+            .iter()
+            .flat_map(|s| message_ids(s, &["tr(", "trf("]))
+        {
+            match en.get(id.as_str()) {
+                None => missing.push(id),
+                Some(t) => assert_eq!(
+                    t.matches("{}").count(),
+                    id.matches("{}").count(),
+                    "占位符个数不一致: {:?}",
+                    id
+                ),
+            }
+        }
+        let plural: HashMap<&str, (&str, &str)> = EN_PLURAL
+            .iter()
+            .map(|&(id, one, other)| (id, (one, other)))
+            .collect();
+        assert_eq!(plural.len(), EN_PLURAL.len(), "EN_PLURAL 中有重复的消息 ID");
+        for id in sources.iter().flat_map(|s| message_ids(s, &["trn("])) {
+            match plural.get(id.as_str()) {
+                None => missing.push(id),
+                Some((one, other)) => {
+                    let n = id.matches("{}").count();
+                    assert_eq!(one.matches("{}").count(), n, "占位符个数不一致: {:?}", id);
+                    assert_eq!(other.matches("{}").count(), n, "占位符个数不一致: {:?}", id);
+                }
+            }
+        }
+        // warn_io 的上下文是常量，不经过 tr("...") 字面量
+        missing.extend(
+            [
+                CTX_READ_DIR,
+                CTX_READ_ENTRY,
+                CTX_METADATA,
+                CTX_BROKEN_LINK,
+                CTX_LINK_SEEN,
+                CTX_DIR_SEEN,
+                CTX_READ_FILE,
+                CTX_CACHE_READ,
+                CTX_CACHE_WRITE,
+                CTX_HISTORY_WRITE,
+            ]
+            .into_iter()
+            .filter(|c| !en.contains_key(c))
+            .map(String::from),
+        );
+        assert!(missing.is_empty(), "EN 目录缺少: {:#?}", missing);
+    }
+
+    #[test]
+    fn test_lang_selection() {
+        assert_eq!(Lang::from_locale("zh_CN.UTF-8"), Lang::Zh);
+        assert_eq!(Lang::from_locale("en_US.UTF-8"), Lang::En);
+        assert_eq!(Lang::from_locale("C"), Lang::En);
+        assert_eq!(lookup(Lang::Zh, "目录:"), "目录:");
+        assert_eq!(lookup(Lang::En, "目录:"), "Directory:");
+        assert_eq!(lookup(Lang::En, "没有这条消息"), "没有这条消息");
+        assert_eq!(fill("{} of {}", &[&1, &"b"]), "1 of b");
+        assert_eq!(lookup_n(Lang::En, "{} 个项目", 1), "{} item");
+        assert_eq!(lookup_n(Lang::En, "{} 个项目", 0), "{} items");
+        assert_eq!(lookup_n(Lang::Zh, "{} 个项目", 1), "{} 个项目");
+    }
+}
@@ -0,0 +1,63 @@
+//! yp：高性能的目录空间占用统计。
+//!
+//! 命令行工具 `yp` 是本库的一层薄封装；其他程序可以直接通过 [`Scanner`] 复用同一套扫描实现：
+//!
+//! ```no_run
+//! use yp::Scanner;
+//!
+//! let report = Scanner::new(".").recursive(true).exclude(".git").scan()?;
+//! for entry in report.entries().filter(|e| e.is_dir()) {
+//!     println!("{:>12}  {}", entry.size(), entry.path().display());
+//! }
+//! for w in report.warnings() {
+//!     eprintln!("warning: {}", w);
+//! }
+//! # Ok::<(), yp::AppError>(())
+//! ```
+//!
+//! 只需聚合而不需要完整条目列表时，用 [`Scanner::visit`] 配合自定义 [`Visitor`]，
+//! 遍历过程中逐项回调，不在内存中保留扫描树。
+//!
+//! 告警与错误信息默认使用中文，可用 [`set_lang`] 切换为英文（进程级）。
+//!
+//! 公开 API（本页列出的类型与方法）遵循语义化版本；`cli` 模块仅供二进制使用，不在此列。
+//! [`AppError`]、[`ScanEntry`]、[`DirStats`] 与 [`VisitEntry`] 标记为 `#[non_exhaustive]`，后续版本可能新增变体或字段。
+#![warn(missing_docs)]
+
+mod budget;
+mod cache;
+#[doc(hidden)]
+pub mod cli;
+mod config;
+mod dupes;
+mod empty;
+mod error;
+mod exclude;
+mod filter;
+mod format;
+mod histogram;
+mod history;
+mod i18n;
+mod links;
+mod progress;
+mod reclaim;
+mod render;
+mod report;
+mod scan;
+mod scanner;
+mod sort;
+mod threads;
+mod visit;
+mod walk;
+mod warnings;
+mod watch;
+
+#[cfg(test)]
+mod test_util;
+
+pub use error::{AppError, AppResult};
+pub use i18n::{Lang, set_lang};
+pub use report::{DirReport, EntryRef, ScanEntry};
+pub use scan::{DirStats, EntryKind, FollowSymlinks};
+pub use scanner::Scanner;
+pub use visit::{VisitEntry, Visitor};
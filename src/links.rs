@@ -0,0 +1,96 @@
+//! 失效符号链接检查（`--broken-links`）。
+
+use crate::error::AppResult;
+use crate::exclude::ExcludeSet;
+use crate::scan::{ScanHooks, visit_directory};
+use crate::visit::{VisitEntry, Visitor};
+use crate::warnings::WarningTracker;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+
+// ---- 失效链接 ----
+//
+// 复用 Visitor 遍历：不建扫描树，只对遇到的符号链接多做一次跟随 stat。
+// 已被 --follow-symlinks 成功跟随的链接（via_link）自然不是失效链接。
+
+#[derive(Debug, Clone)]
+pub(crate) struct BrokenLink {
+    pub(crate) path: PathBuf,
+    /// 链接内容；readlink 失败时为 None
+    pub(crate) target: Option<PathBuf>,
+    /// 解析目标时的错误（不存在、循环等）
+    pub(crate) error: String,
+}
+
+#[derive(Default)]
+struct BrokenLinkCollector {
+    found: Mutex<Vec<BrokenLink>>,
+}
+
+impl Visitor for BrokenLinkCollector {
+    fn entry(&self, e: &VisitEntry<'_>) {
+        if !e.is_symlink || e.via_link {
+            return;
+        }
+        if let Err(err) = fs::metadata(e.path) {
+            let link = BrokenLink {
+                path: e.path.to_path_buf(),
+                target: e.target.map(Path::to_path_buf),
+                error: err.to_string(),
+            };
+            self.found
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(link);
+        }
+    }
+}
+
+/// 列出 `root` 下的全部失效符号链接（按路径排序），以及扫描是否被中断。
+pub(crate) fn find_broken_links(
+    root: &Path,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+    hooks: ScanHooks,
+) -> AppResult<(Vec<BrokenLink>, bool)> {
+    let collector = BrokenLinkCollector::default();
+    let hooks = ScanHooks {
+        visitor: Some(&collector),
+        ..hooks
+    };
+    visit_directory(root, excludes, warnings, hooks)?;
+    let incomplete = hooks.cancel.is_some_and(|c| c.load(Ordering::Relaxed));
+    let mut found = collector
+        .found
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner());
+    found.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok((found, incomplete))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::test_util::Fixture;
+    use std::os::unix::fs::symlink;
+
+    #[test]
+    fn test_find_broken_links() {
+        let fx = Fixture::new("yp_broken_links");
+        let sub = fx.dir("sub");
+        fx.file("ok.txt", b"x");
+        symlink("ok.txt", fx.path("good")).expect("symlink good");
+        symlink("../gone", sub.join("dangling")).expect("symlink dangling");
+        symlink("self", fx.path("self")).expect("symlink self");
+
+        let (found, incomplete) =
+            find_broken_links(fx.root(), &fx.excludes, &fx.warnings, ScanHooks::default())
+                .expect("find_broken_links should succeed");
+        assert!(!incomplete);
+        let paths: Vec<_> = found.iter().map(|l| l.path.clone()).collect();
+        assert_eq!(paths, [fx.path("self"), sub.join("dangling")]);
+        assert_eq!(found[1].target.as_deref(), Some(Path::new("../gone")));
+    }
+}
@@ -0,0 +1,176 @@
+//! 扫描期间 stderr 上的进度行。
+
+use crate::format::{SizeFormat, get_terminal_width, truncate_filename};
+use crate::i18n::trf;
+use crate::warnings::WarningTracker;
+use colored::*;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use unicode_width::UnicodeWidthStr;
+
+pub(crate) const PROGRESS_TICK: Duration = Duration::from_millis(150);
+
+// ---- 进度指示 ----
+//
+// 计数器均为 Relaxed 原子量：只用于展示，不参与结果计算。
+// 当前路径只在渲染线程请求时（want_path）才由 worker 写入，避免每个目录都加锁/分配。
+
+pub(crate) const CLEAR_LINE: &str = "\r\x1b[2K";
+
+#[derive(Debug)]
+pub(crate) struct Progress {
+    pub(crate) dirs: AtomicU64,
+    pub(crate) files: AtomicU64,
+    pub(crate) bytes: AtomicU64,
+    pub(crate) want_path: AtomicBool,
+    pub(crate) current: Mutex<PathBuf>,
+    pub(crate) start: Instant,
+}
+
+impl Progress {
+    pub(crate) fn new() -> Self {
+        Self {
+            dirs: AtomicU64::new(0),
+            files: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            want_path: AtomicBool::new(true),
+            current: Mutex::new(PathBuf::new()),
+            start: Instant::now(),
+        }
+    }
+
+    pub(crate) fn dir(&self, path: &Path) {
+        self.dirs.fetch_add(1, Ordering::Relaxed);
+        if self.want_path.load(Ordering::Relaxed)
+            && self.want_path.swap(false, Ordering::Relaxed)
+            && let Ok(mut cur) = self.current.try_lock()
+        {
+            cur.clear();
+            cur.push(path);
+        }
+    }
+
+    pub(crate) fn files(&self, files: u64, bytes: u64) {
+        self.files.fetch_add(files, Ordering::Relaxed);
+        self.bytes.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn render_line(&self, width: usize, size_fmt: SizeFormat) -> String {
+        let head = trf(
+            "扫描中 {}s | 目录 {} | 文件 {} | {} | ",
+            &[
+                &format!("{:.1}", self.start.elapsed().as_secs_f64()),
+                &self.dirs.load(Ordering::Relaxed),
+                &self.files.load(Ordering::Relaxed),
+                &size_fmt.format(self.bytes.load(Ordering::Relaxed)),
+            ],
+        );
+        let cur = self
+            .current
+            .lock()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        self.want_path.store(true, Ordering::Relaxed);
+        let path_w = width.saturating_sub(head.width() + 1);
+        format!("{}{}", head, truncate_filename(&cur, path_w))
+    }
+}
+
+/// 默认仅在 stderr 为终端时显示进度；`--progress` / `--no-progress` 可强制开关。
+pub(crate) fn progress_enabled(force_on: bool, force_off: bool) -> bool {
+    if force_off {
+        return false;
+    }
+    force_on || io::stderr().is_terminal()
+}
+
+/// 在后台线程周期性刷新 stderr 上的进度行；`f` 返回后先清除该行再返回其结果。
+pub(crate) fn with_progress<T>(
+    enabled: bool,
+    size_fmt: SizeFormat,
+    warnings: &WarningTracker,
+    f: impl FnOnce(Option<&Progress>) -> T,
+) -> T {
+    if !enabled {
+        return f(None);
+    }
+
+    let progress = Progress::new();
+    let done = AtomicBool::new(false);
+    let width = get_terminal_width();
+
+    warnings.progress_active.store(true, Ordering::Relaxed);
+    let out = std::thread::scope(|s| {
+        let painter = s.spawn(|| {
+            loop {
+                std::thread::park_timeout(PROGRESS_TICK);
+                if done.load(Ordering::Relaxed) {
+                    break;
+                }
+                let line = progress.render_line(width, size_fmt);
+                let mut err = io::stderr().lock();
+                let _ = write!(err, "{}{}", CLEAR_LINE, line.dimmed());
+                let _ = err.flush();
+            }
+        });
+
+        let out = f(Some(&progress));
+        done.store(true, Ordering::Relaxed);
+        painter.thread().unpark();
+        out
+    });
+    // scope 返回时渲染线程已退出，此时清除进度行不会再被覆盖
+    warnings.progress_active.store(false, Ordering::Relaxed);
+    eprint!("{}", CLEAR_LINE);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exclude::ExcludeSet;
+    use crate::scan::{ScanHooks, analyze_directory};
+    use crate::test_util::TempDirGuard;
+    use std::fs;
+
+    #[test]
+    fn test_progress_counts_scan() {
+        let tmp = TempDirGuard::new("yp_progress_counts");
+        fs::create_dir_all(tmp.path().join("d")).expect("failed to create dir");
+        fs::write(tmp.path().join("d").join("f"), b"1234").expect("failed to write f");
+        fs::write(tmp.path().join("g"), b"12").expect("failed to write g");
+
+        let warnings = WarningTracker::new();
+        let excludes = ExcludeSet {
+            patterns: Vec::new(),
+            has_abs: false,
+        };
+        let progress = Progress::new();
+        analyze_directory(
+            tmp.path(),
+            true,
+            &excludes,
+            &warnings,
+            ScanHooks {
+                progress: Some(&progress),
+                ..Default::default()
+            },
+        )
+        .expect("analyze_directory should succeed");
+
+        assert_eq!(progress.dirs.load(Ordering::Relaxed), 2);
+        assert_eq!(progress.files.load(Ordering::Relaxed), 2);
+        assert_eq!(progress.bytes.load(Ordering::Relaxed), 6);
+        let line = progress.render_line(100, SizeFormat::default());
+        // 语言是进程级设置，只检查与语言无关的数值字段，避免与并行测试互相影响
+        let fields: Vec<&str> = line.split(" | ").collect();
+        assert!(
+            fields[1].ends_with(" 2") && fields[2].ends_with(" 2"),
+            "进度行应包含目录数与文件数：{}",
+            line
+        );
+    }
+}
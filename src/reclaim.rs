@@ -0,0 +1,262 @@
+//! 可回收的缓存与构建目录（`--reclaimable`）。
+
+use crate::error::AppResult;
+use crate::exclude::ExcludeSet;
+use crate::scan::{DirStats, ScanHooks, visit_directory};
+use crate::visit::Visitor;
+use crate::warnings::WarningTracker;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::Ordering;
+
+// ---- 识别规则 ----
+//
+// 目录名命中后还需满足标记文件条件，避免把同名的普通目录（如随手建的 target/）当作构建产物。
+// 判定放在 exit_dir：此时子树统计已完成，命中的目录大小即为可回收量。
+// 命中目录内部的再次命中（node_modules 里的 node_modules）只计最外层一次。
+
+/// 标记文件的位置
+#[derive(Debug, Clone, Copy)]
+enum Marker {
+    /// 与目录同级（位于项目根）
+    Sibling(&'static str),
+    /// 位于目录内部
+    Inside(&'static str),
+    /// 目录内有合规的 CACHEDIR.TAG（文件须以规范签名开头）
+    CacheTag,
+}
+
+/// Cache Directory Tagging 规范的签名，CACHEDIR.TAG 必须以此开头
+const CACHEDIR_SIGNATURE: &[u8] = b"Signature: 8a477f597d28d172789f06886806bc55";
+
+fn has_cache_tag(dir: &Path) -> bool {
+    let mut head = [0u8; CACHEDIR_SIGNATURE.len()];
+    File::open(dir.join("CACHEDIR.TAG"))
+        .and_then(|mut f| f.read_exact(&mut head))
+        .is_ok_and(|()| head == CACHEDIR_SIGNATURE)
+}
+
+struct Rule {
+    name: &'static str,
+    category: &'static str,
+    /// 任一成立即可；为空表示只按名称识别
+    markers: &'static [Marker],
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        name: "node_modules",
+        category: "node_modules",
+        markers: &[Marker::Sibling("package.json")],
+    },
+    Rule {
+        name: "target",
+        category: "target",
+        markers: &[Marker::Sibling("Cargo.toml")],
+    },
+    Rule {
+        name: ".venv",
+        category: ".venv",
+        markers: &[Marker::Inside("pyvenv.cfg")],
+    },
+    Rule {
+        name: "__pycache__",
+        category: "__pycache__",
+        markers: &[],
+    },
+    Rule {
+        name: ".gradle",
+        category: ".gradle",
+        markers: &[],
+    },
+    // 只认带合规 CACHEDIR.TAG（Cache Directory Tagging 规范）的 .cache；
+    // $HOME/.cache 这类用户级缓存混有配置与数据，不能整体当作可回收
+    Rule {
+        name: ".cache",
+        category: ".cache",
+        markers: &[Marker::CacheTag],
+    },
+    Rule {
+        name: "dist",
+        category: "dist",
+        markers: &[
+            Marker::Sibling("package.json"),
+            Marker::Sibling("pyproject.toml"),
+            Marker::Sibling("setup.py"),
+        ],
+    },
+    // Docker 守护进程的 BuildKit 缓存（/var/lib/docker/buildkit）
+    Rule {
+        name: "buildkit",
+        category: "docker",
+        markers: &[Marker::Inside("cache.db")],
+    },
+    // buildx 本地缓存导出（--cache-to type=local,dest=.buildx-cache）
+    Rule {
+        name: ".buildx-cache",
+        category: "docker",
+        markers: &[Marker::Inside("index.json")],
+    },
+];
+
+fn match_rule(path: &Path) -> Option<&'static Rule> {
+    let name = path.file_name()?;
+    let rule = RULES.iter().find(|r| name == r.name)?;
+    let parent = path.parent()?;
+    let ok = rule.markers.is_empty()
+        || rule.markers.iter().any(|m| match *m {
+            Marker::Sibling(f) => parent.join(f).exists(),
+            Marker::Inside(f) => path.join(f).exists(),
+            Marker::CacheTag => has_cache_tag(path),
+        });
+    ok.then_some(rule)
+}
+
+// ---- 统计 ----
+
+/// 一个可回收目录
+#[derive(Debug, Clone)]
+pub(crate) struct Reclaimable {
+    pub(crate) path: PathBuf,
+    pub(crate) category: &'static str,
+    pub(crate) size: u64,
+}
+
+impl Reclaimable {
+    /// 所属项目根（标记文件所在目录）
+    pub(crate) fn project(&self) -> &Path {
+        self.path.parent().unwrap_or(&self.path)
+    }
+}
+
+/// 按类别汇总
+#[derive(Debug, Clone)]
+pub(crate) struct CategoryTotal {
+    pub(crate) category: &'static str,
+    pub(crate) count: u64,
+    pub(crate) size: u64,
+}
+
+#[derive(Default)]
+struct ReclaimCollector {
+    found: Mutex<Vec<Reclaimable>>,
+}
+
+impl Visitor for ReclaimCollector {
+    fn exit_dir(&self, path: &Path, totals: DirStats) {
+        if let Some(rule) = match_rule(path) {
+            let item = Reclaimable {
+                path: path.to_path_buf(),
+                category: rule.category,
+                size: totals.size,
+            };
+            self.found
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(item);
+        }
+    }
+}
+
+/// 列出 `root` 下的可回收目录（按大小降序）与各类别汇总（按大小降序），以及扫描是否被中断。
+pub(crate) fn find_reclaimable(
+    root: &Path,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+    hooks: ScanHooks,
+) -> AppResult<(Vec<Reclaimable>, Vec<CategoryTotal>, bool)> {
+    let collector = ReclaimCollector::default();
+    visit_directory(
+        root,
+        excludes,
+        warnings,
+        ScanHooks {
+            visitor: Some(&collector),
+            ..hooks
+        },
+    )?;
+    let incomplete = hooks.cancel.is_some_and(|c| c.load(Ordering::Relaxed));
+    let found = collector
+        .found
+        .into_inner()
+        .unwrap_or_else(|e| e.into_inner());
+
+    let paths: HashSet<&Path> = found.iter().map(|r| r.path.as_path()).collect();
+    let mut items: Vec<Reclaimable> = found
+        .iter()
+        .filter(|r| !r.path.ancestors().skip(1).any(|a| paths.contains(a)))
+        .cloned()
+        .collect();
+    items.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+
+    let mut by_category: HashMap<&'static str, CategoryTotal> = HashMap::new();
+    for r in &items {
+        let t = by_category.entry(r.category).or_insert(CategoryTotal {
+            category: r.category,
+            count: 0,
+            size: 0,
+        });
+        t.count += 1;
+        t.size += r.size;
+    }
+    let mut totals: Vec<CategoryTotal> = by_category.into_values().collect();
+    totals.sort_by(|a, b| b.size.cmp(&a.size).then(a.category.cmp(b.category)));
+    Ok((items, totals, incomplete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Fixture;
+
+    #[test]
+    fn test_find_reclaimable() {
+        let fx = Fixture::new("yp_reclaimable");
+        fx.file("web/package.json", b"{}");
+        fx.file("web/node_modules/dep/package.json", b"{}");
+        fx.file("web/node_modules/dep/node_modules/x.js", b"12345");
+        // 没有 Cargo.toml 的 target 不是构建目录
+        fx.file("notes/target/t", b"123");
+        fx.file("app/Cargo.toml", b"");
+        fx.file("app/target/debug/bin", b"12");
+
+        let (items, totals, incomplete) =
+            find_reclaimable(fx.root(), &fx.excludes, &fx.warnings, ScanHooks::default())
+                .expect("find_reclaimable should succeed");
+        assert!(!incomplete);
+        let got: Vec<_> = items.iter().map(|r| (r.path.clone(), r.size)).collect();
+        assert_eq!(
+            got,
+            [(fx.path("web/node_modules"), 7), (fx.path("app/target"), 2)]
+        );
+        assert_eq!(items[0].project(), fx.path("web"));
+        assert_eq!(totals.len(), 2);
+        assert_eq!((totals[0].category, totals[0].count), ("node_modules", 1));
+    }
+
+    #[test]
+    fn test_cache_dir_requires_tag() {
+        let fx = Fixture::new("yp_reclaimable_cache");
+        // 类似 $HOME/.cache：没有 CACHEDIR.TAG，不计入
+        fx.file("home/.cache/app/state", b"1234");
+        // 签名不符的 CACHEDIR.TAG 也不算
+        fx.file(
+            "fake/.cache/CACHEDIR.TAG",
+            b"Signature: not-a-cache-tag-at-all-xxxxxxxxx",
+        );
+        fx.file(
+            "proj/.cache/CACHEDIR.TAG",
+            b"Signature: 8a477f597d28d172789f06886806bc55",
+        );
+
+        let (items, totals, _) =
+            find_reclaimable(fx.root(), &fx.excludes, &fx.warnings, ScanHooks::default())
+                .expect("find_reclaimable should succeed");
+        let got: Vec<_> = items.iter().map(|r| (r.path.clone(), r.size)).collect();
+        assert_eq!(got, [(fx.path("proj/.cache"), 43)]);
+        assert_eq!((totals[0].category, totals[0].count), (".cache", 1));
+    }
+}
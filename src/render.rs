@@ -0,0 +1,1178 @@
+//! 文本/JSON 渲染与 tree 模式。
+
+use crate::budget::Violation;
+use crate::dupes::DuplicateSet;
+use crate::empty::EmptyGroup;
+use crate::error::AppResult;
+use crate::filter::TypeFilter;
+use crate::format::{SizeFormat, format_utc, get_terminal_width, lossy_display, truncate_filename};
+use crate::histogram::{Histogram, HistogramScale};
+use crate::history::{Projection, growth_rate, project};
+use crate::i18n::{tr, trf, trn};
+use crate::links::BrokenLink;
+use crate::reclaim::{CategoryTotal, Reclaimable};
+use crate::report::{ChildIndex, DirReport, EntryRef, EntryView, NodeId, ScanTree};
+use crate::scan::EntryKind;
+use crate::sort::{SortSpec, sort_entries};
+use crate::watch::Growth;
+use colored::*;
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+pub(crate) const BAR_MAX_WIDTH: usize = 40;
+
+// ---- 输出函数 ----
+
+/// 文本渲染选项（report 与 tree 模式共用）
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RenderOptions {
+    pub(crate) size_fmt: SizeFormat,
+    /// report 模式：显示 ASCII 条形图
+    pub(crate) show_chart: bool,
+    /// tree 模式：显示图标
+    pub(crate) show_icon: bool,
+    /// 显示目录的递归文件数/目录数列
+    pub(crate) show_counts: bool,
+    /// 扫描期间在 stderr 显示进度行
+    pub(crate) show_progress: bool,
+    /// 列出的条目类型（`--type`）；tree 模式下目录始终保留以显示层级
+    pub(crate) types: TypeFilter,
+}
+
+/// 预算违规的 JSON 形式
+fn violations_json(violations: &[Violation]) -> serde_json::Value {
+    violations
+        .iter()
+        .map(|v| {
+            serde_json::json!({
+                "rule": v.rule,
+                "path": v.path.to_string_lossy(),
+                "size": v.size,
+                "limit": v.limit,
+            })
+        })
+        .collect()
+}
+
+/// 直接流式写到 stdout，不在内存中拼出整份 JSON 文本。
+/// 有预算违规时追加 `budget_violations` 字段；全部通过时输出与未设预算时相同。
+pub(crate) fn output_json(report: &DirReport, violations: &[Violation]) -> AppResult<()> {
+    #[derive(serde::Serialize)]
+    struct WithViolations<'a> {
+        #[serde(flatten)]
+        report: &'a DirReport,
+        budget_violations: serde_json::Value,
+    }
+
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    if violations.is_empty() {
+        serde_json::to_writer_pretty(&mut out, report)?;
+    } else {
+        let v = WithViolations {
+            report,
+            budget_violations: violations_json(violations),
+        };
+        serde_json::to_writer_pretty(&mut out, &v)?;
+    }
+    // 写出失败与序列化失败同样归为 JSON 错误（serde_json 内部对 io 错误也是如此处理）
+    writeln!(out)
+        .and_then(|_| out.flush())
+        .map_err(serde_json::Error::io)?;
+    Ok(())
+}
+
+pub(crate) fn output_json_summary(report: &DirReport, violations: &[Violation]) -> AppResult<()> {
+    let (file_cnt, dir_cnt) = report.entries().fold((0usize, 0usize), |(f, d), e| {
+        if e.is_dir() { (f, d + 1) } else { (f + 1, d) }
+    });
+
+    let mut summary = serde_json::json!({
+        "path": report.path.to_string_lossy(),
+        "total_size": report.total_size,
+        "item_count": report.entries().len(),
+        "file_count": file_cnt,
+        "dir_count": dir_cnt,
+        "incomplete": report.incomplete
+    });
+    if !violations.is_empty() {
+        summary["budget_violations"] = violations_json(violations);
+    }
+
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+    Ok(())
+}
+
+pub(crate) fn print_incomplete_notice() {
+    println!(
+        "{} {}",
+        tr("注意:").red().bold(),
+        tr("扫描被中断（Ctrl-C），以下为不完整的部分结果").red()
+    );
+}
+
+pub(crate) fn output_summary(report: &DirReport, size_fmt: SizeFormat) {
+    let w = get_terminal_width();
+    println!("{}", "═".repeat(w).cyan().bold());
+    if report.incomplete {
+        print_incomplete_notice();
+    }
+    println!(
+        "{} {}",
+        tr("目录:").green().bold(),
+        report.path.to_string_lossy().yellow()
+    );
+    println!(
+        "{} {}",
+        tr("总大小:").green().bold(),
+        size_fmt.format(report.total_size).cyan().bold()
+    );
+    println!(
+        "{} {}",
+        tr("项目数:").green().bold(),
+        trn(
+            "{} 个项目",
+            report.entries().len() as u64,
+            &[&report.entries().len().to_string().yellow().bold()]
+        )
+    );
+    println!("{}", "═".repeat(w).cyan().bold());
+}
+
+/// 显示名称：符号链接显示为 `name -> target`；经 `--follow-symlinks` 跟随的链接在名称后标记 `@`。
+pub(crate) fn display_name<'a>(entry: &EntryRef<'a>) -> Cow<'a, str> {
+    let name = lossy_display(entry.name());
+    let marker = if entry.via_link() { "@" } else { "" };
+    match entry.target() {
+        Some(t) => Cow::Owned(format!("{}{} -> {}", name, marker, t.display())),
+        None if entry.via_link() => Cow::Owned(format!("{}@", name)),
+        None => name,
+    }
+}
+
+/// 类型图标：目录、文件、符号链接与各类特殊文件各不相同。
+pub(crate) fn kind_icon(entry: &EntryRef) -> &'static str {
+    match entry.kind() {
+        EntryKind::Symlink => "🔗",
+        _ if entry.is_dir() => "📁",
+        EntryKind::Socket => "🔌",
+        EntryKind::Fifo => "📨",
+        EntryKind::CharDevice => "📟",
+        EntryKind::BlockDevice => "💽",
+        EntryKind::Other => "❔",
+        EntryKind::File | EntryKind::Dir => "📄",
+    }
+}
+
+/// 文件数/目录数列（`--counts`）。非目录条目留空，避免每个文件都显示"1 文件"。
+pub(crate) fn format_counts<T: EntryView>(entry: &T) -> String {
+    if entry.is_dir() {
+        format!(
+            "{} {}",
+            trn("{} 文件", entry.file_count(), &[&entry.file_count()]),
+            trn("{} 目录", entry.dir_count(), &[&entry.dir_count()])
+        )
+    } else {
+        String::new()
+    }
+}
+
+/// watch 模式的变化标注：增长为红色 `▲ +大小 (+速率/s)`，缩小为绿色 `▼`，不变为空。
+pub(crate) fn growth_label(delta: i64, growth: &Growth, size_fmt: SizeFormat) -> String {
+    if delta == 0 {
+        return String::new();
+    }
+    let secs = growth.elapsed.as_secs_f64().max(0.001);
+    let abs = delta.unsigned_abs();
+    let rate = size_fmt.format((abs as f64 / secs) as u64);
+    if delta > 0 {
+        format!(" ▲ +{} (+{}/s)", size_fmt.format(abs), rate)
+            .red()
+            .bold()
+            .to_string()
+    } else {
+        format!(" ▼ -{} (-{}/s)", size_fmt.format(abs), rate)
+            .green()
+            .to_string()
+    }
+}
+
+/// `growth` 为 watch 模式下与上一次刷新的差异：增长的条目高亮名称并在行尾标注增量与速率。
+pub(crate) fn output_text(report: &DirReport, opts: &RenderOptions, growth: Option<&Growth>) {
+    let display_width = get_terminal_width();
+    let show_chart = opts.show_chart;
+
+    // 先扫一遍求列宽（bytes 模式下数字可能超过默认 12 列），逐行输出时再格式化，
+    // 避免为每个条目常驻一份字符串
+    let size_width = report
+        .entries()
+        .map(|e| opts.size_fmt.format(e.size()).width())
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    let count_width = if opts.show_counts {
+        report
+            .entries()
+            .map(|e| format_counts(&e).width())
+            .max()
+            .unwrap_or(0)
+    } else {
+        0
+    };
+    let chart_width = if show_chart { BAR_MAX_WIDTH + 2 } else { 0 };
+    let icon_width = 3;
+    let spacing = 2;
+    let count_col = if count_width > 0 { count_width + 1 } else { 0 };
+
+    let used_width = icon_width + size_width + count_col + chart_width + spacing * 2;
+    let available_width = display_width.saturating_sub(used_width);
+    let filename_width = if show_chart {
+        available_width.clamp(20, 50)
+    } else {
+        available_width.clamp(30, 80)
+    };
+    let actual_width =
+        icon_width + filename_width + size_width + count_col + chart_width + spacing * 2;
+
+    println!("{}", "═".repeat(actual_width).cyan().bold());
+    if report.incomplete {
+        print_incomplete_notice();
+    }
+    println!(
+        "{} {}",
+        tr("目录:").green().bold(),
+        report.path.to_string_lossy().yellow()
+    );
+    let total_growth = growth
+        .map(|g| growth_label(g.total_delta(report), g, opts.size_fmt))
+        .unwrap_or_default();
+    println!(
+        "{} {}{}",
+        tr("总大小:").green().bold(),
+        opts.size_fmt.format(report.total_size).cyan().bold(),
+        total_growth
+    );
+    println!("{}", "═".repeat(actual_width).cyan().bold());
+
+    if report.order.is_empty() {
+        println!("{}", tr("目录为空").yellow());
+        return;
+    }
+
+    // [FIX-STYLE-8] 改为 unwrap_or(0)，无需依赖前面 is_empty 早返回的隐式保证
+    let max_size = report.entries().map(|e| e.size()).max().unwrap_or(0);
+
+    for entry in report.entries() {
+        let size_str = opts.size_fmt.format(entry.size());
+        let counts = if count_width > 0 {
+            format!(" {:>count_width$}", format_counts(&entry))
+        } else {
+            String::new()
+        };
+        let type_icon = kind_icon(&entry);
+
+        let name_cow = display_name(&entry);
+        let truncated_name = truncate_filename(&name_cow, filename_width);
+        let colored_name = if entry.is_dir() {
+            truncated_name.blue().bold()
+        } else {
+            truncated_name.white()
+        };
+
+        let padding = " ".repeat(filename_width.saturating_sub(truncated_name.width()));
+        let delta = growth.map_or(0, |g| g.delta(&entry));
+        let colored_name = if delta > 0 {
+            colored_name.yellow().bold()
+        } else {
+            colored_name
+        };
+        let change = growth
+            .map(|g| growth_label(delta, g, opts.size_fmt))
+            .unwrap_or_default();
+
+        if show_chart {
+            let bar_len = if max_size == 0 {
+                0
+            } else {
+                (((entry.size() as u128) * (BAR_MAX_WIDTH as u128)) / (max_size as u128)) as usize
+            }
+            .min(BAR_MAX_WIDTH);
+
+            let bar = "█".repeat(bar_len);
+            let bar_colored = if entry.is_dir() {
+                bar.blue()
+            } else {
+                bar.green()
+            };
+
+            println!(
+                "{} {}{} {:>size_width$}{} [{}{}]{}",
+                type_icon,
+                colored_name,
+                padding,
+                size_str.cyan(),
+                counts.dimmed(),
+                bar_colored,
+                " ".repeat(BAR_MAX_WIDTH - bar_len),
+                change
+            );
+        } else {
+            println!(
+                "{} {}{} {:>size_width$}{}{}",
+                type_icon,
+                colored_name,
+                padding,
+                size_str.cyan(),
+                counts.dimmed(),
+                change
+            );
+        }
+    }
+
+    println!("{}", "═".repeat(actual_width).cyan().bold());
+    println!(
+        "{} {}",
+        tr("共计:").green().bold(),
+        trn(
+            "{} 个项目",
+            report.entries().len() as u64,
+            &[&report.entries().len().to_string().yellow().bold()]
+        )
+    );
+}
+
+// ---- 大小分布 ----
+
+/// 桶边界的精确标签：pow2 用 1024 进制（1 KiB、512 KiB），decade 用 1000 进制（10 kB）。
+/// 边界都是刻度的整数幂，取能整除的最大单位即可避免小数。
+fn bound_label(n: u64, scale: HistogramScale) -> String {
+    let (base, names): (u64, [&str; 7]) = match scale {
+        HistogramScale::Pow2 => (1024, ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"]),
+        HistogramScale::Decade => (1000, ["B", "kB", "MB", "GB", "TB", "PB", "EB"]),
+    };
+    let mut v = n;
+    let mut unit = 0;
+    while v >= base && v.is_multiple_of(base) && unit + 1 < names.len() {
+        v /= base;
+        unit += 1;
+    }
+    format!("{} {}", v, names[unit])
+}
+
+pub(crate) fn output_histogram(
+    root: &Path,
+    hist: &Histogram,
+    incomplete: bool,
+    opts: &RenderOptions,
+) {
+    let labels: Vec<String> = hist
+        .buckets
+        .iter()
+        .map(|b| match (b.min, b.max) {
+            (0, _) => "0 B".to_string(),
+            (min, Some(max)) => format!(
+                "{} – {}",
+                bound_label(min, hist.scale),
+                bound_label(max, hist.scale)
+            ),
+            (min, None) => format!("≥ {}", bound_label(min, hist.scale)),
+        })
+        .collect();
+    let label_width = labels.iter().map(|l| l.width()).max().unwrap_or(0);
+    let count_width = hist
+        .buckets
+        .iter()
+        .map(|b| b.files.to_string().len())
+        .max()
+        .unwrap_or(0);
+    let size_width = hist
+        .buckets
+        .iter()
+        .map(|b| opts.size_fmt.format(b.bytes).width())
+        .max()
+        .unwrap_or(0)
+        .max(12);
+    let chart_width = if opts.show_chart {
+        BAR_MAX_WIDTH + 3
+    } else {
+        0
+    };
+    let files_words: Vec<String> = hist
+        .buckets
+        .iter()
+        .map(|b| trn(" 个文件", b.files, &[]))
+        .collect();
+    let word_width = files_words.iter().map(|w| w.width()).max().unwrap_or(0);
+    let actual_width = label_width + count_width + word_width + size_width + chart_width + 2;
+
+    println!("{}", "═".repeat(actual_width).cyan().bold());
+    if incomplete {
+        print_incomplete_notice();
+    }
+    println!(
+        "{} {}",
+        tr("目录:").green().bold(),
+        lossy_display(root).yellow()
+    );
+    println!(
+        "{} {}",
+        tr("总计:").green().bold(),
+        trn(
+            "{} 个文件，{}",
+            hist.total_files(),
+            &[
+                &hist.total_files().to_string().yellow().bold(),
+                &opts.size_fmt.format(hist.total_bytes()).cyan().bold()
+            ]
+        )
+    );
+    println!("{}", "═".repeat(actual_width).cyan().bold());
+
+    if hist.buckets.is_empty() {
+        println!("{}", tr("没有普通文件").yellow());
+        return;
+    }
+
+    // 条形长度按文件数：分布形状比字节数更能反映块大小与分层的取舍
+    let max_files = hist.buckets.iter().map(|b| b.files).max().unwrap_or(0);
+    for ((b, label), word) in hist.buckets.iter().zip(&labels).zip(&files_words) {
+        let padding = " ".repeat(label_width - label.width());
+        let line = format!(
+            "{}{} {:>count_width$}{}{} {:>size_width$}",
+            label.white(),
+            padding,
+            b.files.to_string().yellow(),
+            word,
+            " ".repeat(word_width - word.width()),
+            opts.size_fmt.format(b.bytes).cyan()
+        );
+        if opts.show_chart {
+            let bar_len = if max_files == 0 {
+                0
+            } else {
+                (((b.files as u128) * (BAR_MAX_WIDTH as u128)) / (max_files as u128)) as usize
+            }
+            .min(BAR_MAX_WIDTH);
+            println!(
+                "{} [{}{}]",
+                line,
+                "█".repeat(bar_len).green(),
+                " ".repeat(BAR_MAX_WIDTH - bar_len)
+            );
+        } else {
+            println!("{}", line);
+        }
+    }
+    println!("{}", "═".repeat(actual_width).cyan().bold());
+}
+
+pub(crate) fn output_histogram_json(
+    root: &Path,
+    hist: &Histogram,
+    incomplete: bool,
+) -> AppResult<()> {
+    let buckets: Vec<_> = hist
+        .buckets
+        .iter()
+        .map(|b| {
+            serde_json::json!({
+                "min": b.min,
+                "max": b.max,
+                "files": b.files,
+                "bytes": b.bytes,
+            })
+        })
+        .collect();
+    let out = serde_json::json!({
+        "path": root.to_string_lossy(),
+        "scale": hist.scale.as_str(),
+        "buckets": buckets,
+        "total_files": hist.total_files(),
+        "total_size": hist.total_bytes(),
+        "incomplete": incomplete
+    });
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    Ok(())
+}
+
+// ---- 扫描历史 ----
+
+const SECS_PER_DAY: f64 = 86_400.0;
+
+/// 带符号的大小：`+1.00 MiB` / `-512 B`
+fn signed_size(delta: f64, size_fmt: SizeFormat) -> String {
+    let sign = if delta < 0.0 { '-' } else { '+' };
+    format!("{}{}", sign, size_fmt.format(delta.abs().round() as u64))
+}
+
+/// `points` 为按时间排序的 (Unix 秒, 字节)；`until` 为 `--until` 给出的目标大小。
+pub(crate) fn output_history(
+    path: &Path,
+    points: &[(u64, u64)],
+    until: Option<u64>,
+    size_fmt: SizeFormat,
+) {
+    println!(
+        "{} {}",
+        tr("目录:").green().bold(),
+        lossy_display(path).yellow()
+    );
+    if points.is_empty() {
+        println!(
+            "{}",
+            tr("没有该目录的扫描记录（用 --record 记录，且目录需在记录深度内）").yellow()
+        );
+        return;
+    }
+
+    let sizes: Vec<String> = points.iter().map(|&(_, s)| size_fmt.format(s)).collect();
+    let size_width = sizes.iter().map(|s| s.width()).max().unwrap_or(0).max(12);
+    let time_width = "YYYY-MM-DD HH:MM".len();
+    let rule_width = time_width + size_width + 18;
+    println!("{}", "═".repeat(rule_width).cyan().bold());
+    let (time_head, size_head) = (tr("时间 (UTC)"), tr("大小"));
+    println!(
+        "{}{}  {}{}  {}",
+        time_head.bold(),
+        " ".repeat(time_width - time_head.width()),
+        " ".repeat(size_width - size_head.width()),
+        size_head.bold(),
+        tr("变化").bold()
+    );
+    let mut prev = None;
+    for (&(t, s), size) in points.iter().zip(&sizes) {
+        let change = match prev {
+            Some(p) if s > p => signed_size((s - p) as f64, size_fmt).red().to_string(),
+            Some(p) if s < p => signed_size(-((p - s) as f64), size_fmt).green().to_string(),
+            Some(_) => "0".dimmed().to_string(),
+            None => String::new(),
+        };
+        println!(
+            "{}  {:>size_width$}  {}",
+            format_utc(t).white(),
+            size.cyan(),
+            change
+        );
+        prev = Some(s);
+    }
+    println!("{}", "═".repeat(rule_width).cyan().bold());
+
+    match growth_rate(points) {
+        Some(rate) => println!(
+            "{} {}",
+            tr("增长速率:").green().bold(),
+            trf(
+                "{}/天（{} 次记录线性拟合）",
+                &[
+                    &signed_size(rate * SECS_PER_DAY, size_fmt).yellow().bold(),
+                    &points.len()
+                ]
+            )
+        ),
+        None => println!(
+            "{} {}",
+            tr("增长速率:").green().bold(),
+            tr("至少需要两个不同时间的记录").dimmed()
+        ),
+    }
+    if let Some(target) = until {
+        let target_str = size_fmt.format(target);
+        let line = match project(points, target) {
+            Projection::Reached => trf("已达到 {}", &[&target_str]).red().bold().to_string(),
+            Projection::Never => trf("按当前趋势不会达到 {}", &[&target_str])
+                .green()
+                .to_string(),
+            Projection::At(t) => {
+                let last = points.last().map_or(t, |&(lt, _)| lt);
+                let days = t.saturating_sub(last) as f64 / SECS_PER_DAY;
+                let after = if days >= 1.0 {
+                    trf("{} 天", &[&format!("{:.1}", days)])
+                } else if days * 24.0 >= 1.0 {
+                    trf("{} 小时", &[&format!("{:.1}", days * 24.0)])
+                } else {
+                    let minutes = (t.saturating_sub(last) / 60).max(1);
+                    trn("{} 分钟", minutes, &[&minutes])
+                };
+                trf(
+                    "预计 {} (UTC) 达到 {}（最近一次记录后约 {}）",
+                    &[&format_utc(t), &target_str, &after],
+                )
+                .yellow()
+                .to_string()
+            }
+        };
+        println!("{} {}", tr("预测:").green().bold(), line);
+    }
+}
+
+pub(crate) fn output_history_json(
+    path: &Path,
+    points: &[(u64, u64)],
+    until: Option<u64>,
+) -> AppResult<()> {
+    let records: Vec<_> = points
+        .iter()
+        .map(|&(t, s)| serde_json::json!({ "time": t, "size": s }))
+        .collect();
+    let projection = until.map(|target| {
+        let (reached, eta) = match project(points, target) {
+            Projection::Reached => (true, None),
+            Projection::Never => (false, None),
+            Projection::At(t) => (false, Some(t)),
+        };
+        serde_json::json!({ "size": target, "reached": reached, "eta": eta })
+    });
+    let out = serde_json::json!({
+        "path": path.to_string_lossy(),
+        "records": records,
+        "rate_per_day": growth_rate(points).map(|r| r * SECS_PER_DAY),
+        "until": projection
+    });
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    Ok(())
+}
+
+// ---- 预算 ----
+
+pub(crate) fn output_budget_violations(violations: &[Violation], size_fmt: SizeFormat) {
+    println!(
+        "{} {}",
+        tr("超出预算:").red().bold(),
+        trn(
+            "{} 条规则超限",
+            violations.len() as u64,
+            &[&violations.len().to_string().yellow().bold()]
+        )
+    );
+    for v in violations {
+        println!(
+            "  {}  {} > {}  {}",
+            lossy_display(&v.path).white(),
+            size_fmt.format(v.size).red().bold(),
+            size_fmt.format(v.limit).cyan(),
+            format!("({})", v.rule).dimmed()
+        );
+    }
+}
+
+// ---- 失效链接 ----
+
+pub(crate) fn output_broken_links(root: &Path, links: &[BrokenLink], incomplete: bool) {
+    if incomplete {
+        print_incomplete_notice();
+    }
+    println!(
+        "{} {}",
+        tr("目录:").green().bold(),
+        lossy_display(root).yellow()
+    );
+    if links.is_empty() {
+        println!("{}", tr("未发现失效的符号链接").green());
+        return;
+    }
+    for l in links {
+        let target = l
+            .target
+            .as_deref()
+            .map(lossy_display)
+            .unwrap_or(Cow::Borrowed("?"));
+        println!(
+            "{} -> {} {}",
+            lossy_display(&l.path).white(),
+            target.red(),
+            format!("({})", l.error).dimmed()
+        );
+    }
+    println!(
+        "{} {}",
+        tr("共计:").green().bold(),
+        trn(
+            "{} 个失效链接",
+            links.len() as u64,
+            &[&links.len().to_string().yellow().bold()]
+        )
+    );
+}
+
+pub(crate) fn output_broken_links_json(
+    root: &Path,
+    links: &[BrokenLink],
+    incomplete: bool,
+) -> AppResult<()> {
+    let items: Vec<_> = links
+        .iter()
+        .map(|l| {
+            serde_json::json!({
+                "path": l.path.to_string_lossy(),
+                "target": l.target.as_ref().map(|t| t.to_string_lossy()),
+                "error": l.error,
+            })
+        })
+        .collect();
+    let out = serde_json::json!({
+        "path": root.to_string_lossy(),
+        "broken_links": items,
+        "incomplete": incomplete
+    });
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    Ok(())
+}
+
+// ---- 重复文件 ----
+
+pub(crate) fn output_duplicates(
+    root: &Path,
+    sets: &[DuplicateSet],
+    incomplete: bool,
+    size_fmt: SizeFormat,
+) {
+    if incomplete {
+        print_incomplete_notice();
+    }
+    println!(
+        "{} {}",
+        tr("目录:").green().bold(),
+        lossy_display(root).yellow()
+    );
+    if sets.is_empty() {
+        println!("{}", tr("未发现重复文件").green());
+        return;
+    }
+    for set in sets {
+        println!(
+            "{} × {}  {}",
+            size_fmt.format(set.size).cyan(),
+            set.paths.len().to_string().yellow(),
+            trf("(浪费 {})", &[&size_fmt.format(set.wasted())]).red()
+        );
+        for p in &set.paths {
+            println!("  {}", lossy_display(p).white());
+        }
+    }
+    let wasted: u64 = sets.iter().map(DuplicateSet::wasted).sum();
+    println!(
+        "{} {}",
+        tr("共计:").green().bold(),
+        trn(
+            "{} 组重复文件，可回收 {}",
+            sets.len() as u64,
+            &[
+                &sets.len().to_string().yellow().bold(),
+                &size_fmt.format(wasted).cyan().bold()
+            ]
+        )
+    );
+}
+
+pub(crate) fn output_duplicates_json(
+    root: &Path,
+    sets: &[DuplicateSet],
+    incomplete: bool,
+) -> AppResult<()> {
+    let items: Vec<_> = sets
+        .iter()
+        .map(|d| {
+            serde_json::json!({
+                "size": d.size,
+                "hash": d.hash,
+                "wasted": d.wasted(),
+                "paths": d.paths.iter().map(|p| p.to_string_lossy()).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let out = serde_json::json!({
+        "path": root.to_string_lossy(),
+        "duplicates": items,
+        "total_wasted": sets.iter().map(DuplicateSet::wasted).sum::<u64>(),
+        "incomplete": incomplete
+    });
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    Ok(())
+}
+
+// ---- 空目录与零字节文件 ----
+
+pub(crate) fn output_empty(root: &Path, groups: &[EmptyGroup], incomplete: bool) {
+    if incomplete {
+        print_incomplete_notice();
+    }
+    println!(
+        "{} {}",
+        tr("目录:").green().bold(),
+        lossy_display(root).yellow()
+    );
+    if groups.is_empty() {
+        println!("{}", tr("未发现空目录或零字节文件").green());
+        return;
+    }
+    let (mut dirs, mut files) = (0u64, 0u64);
+    for g in groups {
+        println!("{}", lossy_display(&g.parent).cyan().bold());
+        for d in &g.dirs {
+            let nested = if d.nested > 0 {
+                trn(" (含 {} 个空子目录)", d.nested, &[&d.nested])
+                    .dimmed()
+                    .to_string()
+            } else {
+                String::new()
+            };
+            println!("  {}/{}", lossy_display(&d.path).blue(), nested);
+        }
+        for f in &g.files {
+            println!("  {}", lossy_display(f).white());
+        }
+        dirs += g.dirs.len() as u64;
+        files += g.files.len() as u64;
+    }
+    println!(
+        "{} {}",
+        tr("共计:").green().bold(),
+        trf(
+            "{}，{}",
+            &[
+                &trn("{} 个空目录", dirs, &[&dirs.to_string().yellow().bold()]),
+                &trn(
+                    "{} 个零字节文件",
+                    files,
+                    &[&files.to_string().yellow().bold()]
+                )
+            ]
+        )
+    );
+}
+
+pub(crate) fn output_empty_json(
+    root: &Path,
+    groups: &[EmptyGroup],
+    incomplete: bool,
+) -> AppResult<()> {
+    let items: Vec<_> = groups
+        .iter()
+        .map(|g| {
+            serde_json::json!({
+                "parent": g.parent.to_string_lossy(),
+                "dirs": g.dirs.iter().map(|d| serde_json::json!({
+                    "path": d.path.to_string_lossy(),
+                    "nested_dirs": d.nested,
+                })).collect::<Vec<_>>(),
+                "files": g.files.iter().map(|p| p.to_string_lossy()).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let out = serde_json::json!({
+        "path": root.to_string_lossy(),
+        "groups": items,
+        "incomplete": incomplete
+    });
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    Ok(())
+}
+
+// ---- 可回收目录 ----
+
+/// 文本输出列出的最大条目数（JSON 输出全部）
+pub(crate) const RECLAIM_TOP: usize = 20;
+
+pub(crate) fn output_reclaimable(
+    root: &Path,
+    items: &[Reclaimable],
+    totals: &[CategoryTotal],
+    incomplete: bool,
+    size_fmt: SizeFormat,
+) {
+    if incomplete {
+        print_incomplete_notice();
+    }
+    println!(
+        "{} {}",
+        tr("目录:").green().bold(),
+        lossy_display(root).yellow()
+    );
+    if items.is_empty() {
+        println!("{}", tr("未发现可回收的缓存或构建目录").green());
+        return;
+    }
+    let cat_width = totals.iter().map(|t| t.category.width()).max().unwrap_or(0);
+    println!("{}", tr("按类别:").green().bold());
+    for t in totals {
+        println!(
+            "  {:<w$}  {:>10}  {}",
+            t.category.magenta(),
+            size_fmt.format(t.size).cyan(),
+            trn("{} 个目录", t.count, &[&t.count.to_string().yellow()]),
+            w = cat_width
+        );
+    }
+    let shown = items.len().min(RECLAIM_TOP);
+    println!("{}", trf("最大的 {} 项:", &[&shown]).green().bold());
+    for r in &items[..shown] {
+        println!(
+            "  {:>10}  {:<w$}  {}  {}",
+            size_fmt.format(r.size).cyan(),
+            r.category.magenta(),
+            lossy_display(&r.path).white(),
+            trf("(项目: {})", &[&lossy_display(r.project())]).dimmed(),
+            w = cat_width
+        );
+    }
+    let total: u64 = totals.iter().map(|t| t.size).sum();
+    println!(
+        "{} {}",
+        tr("共计:").green().bold(),
+        trn(
+            "{} 项，可回收 {}",
+            items.len() as u64,
+            &[
+                &items.len().to_string().yellow().bold(),
+                &size_fmt.format(total).cyan().bold()
+            ]
+        )
+    );
+}
+
+pub(crate) fn output_reclaimable_json(
+    root: &Path,
+    items: &[Reclaimable],
+    totals: &[CategoryTotal],
+    incomplete: bool,
+) -> AppResult<()> {
+    let categories: Vec<_> = totals
+        .iter()
+        .map(|t| {
+            serde_json::json!({
+                "category": t.category,
+                "count": t.count,
+                "size": t.size,
+            })
+        })
+        .collect();
+    let entries: Vec<_> = items
+        .iter()
+        .map(|r| {
+            serde_json::json!({
+                "path": r.path.to_string_lossy(),
+                "category": r.category,
+                "size": r.size,
+                "project": r.project().to_string_lossy(),
+            })
+        })
+        .collect();
+    let out = serde_json::json!({
+        "path": root.to_string_lossy(),
+        "categories": categories,
+        "items": entries,
+        "total_size": totals.iter().map(|t| t.size).sum::<u64>(),
+        "incomplete": incomplete
+    });
+    println!("{}", serde_json::to_string_pretty(&out)?);
+    Ok(())
+}
+
+// ---- tree 模式 ----
+//
+// 与 report 模式共用 analyze_directory 的扫描结果：每个 inode 只 stat 一次，
+// 渲染期间不再访问文件系统，显示的数值与总大小来自同一次扫描。
+
+pub(crate) struct TreePrintConfig<'a> {
+    pub(crate) render: RenderOptions,
+    pub(crate) sort: SortSpec,
+    pub(crate) term_width: usize,
+    pub(crate) tree: &'a ScanTree,
+    pub(crate) children: &'a ChildIndex,
+    /// watch 模式下与上一次刷新的差异
+    pub(crate) growth: Option<&'a Growth>,
+}
+
+/// tree 模式的完整输出：表头加整棵树；扫描根是文件时只显示这一项。
+pub(crate) fn output_tree(
+    report: &DirReport,
+    render: RenderOptions,
+    sort: SortSpec,
+    growth: Option<&Growth>,
+) {
+    let root_display = lossy_display(&report.path);
+    if !report.tree.node(0).is_dir {
+        println!(
+            "{} {}",
+            tr("路径:").green().bold(),
+            root_display.as_ref().yellow()
+        );
+        let name = lossy_display(report.tree.name(0));
+        let size_str = render.size_fmt.format(report.total_size);
+        if render.show_icon {
+            println!("└── 📄 {} {}", name.white(), size_str.cyan());
+        } else {
+            println!("└── {} {}", name.white(), size_str.cyan());
+        }
+        return;
+    }
+
+    println!("{} {}", tr("目录:").green().bold(), root_display.yellow());
+    if report.incomplete {
+        print_incomplete_notice();
+    }
+    let total_growth = growth
+        .map(|g| growth_label(g.total_delta(report), g, render.size_fmt))
+        .unwrap_or_default();
+    println!(
+        "{} {}{}",
+        tr("总大小:").green().bold(),
+        render.size_fmt.format(report.total_size).cyan().bold(),
+        total_growth
+    );
+
+    let children = report.tree.child_index();
+    let cfg = TreePrintConfig {
+        render,
+        sort,
+        term_width: get_terminal_width(),
+        tree: &report.tree,
+        children: &children,
+        growth,
+    };
+    print_tree_dir(0, "", &cfg);
+}
+
+pub(crate) fn print_tree_dir(dir: NodeId, prefix: &str, cfg: &TreePrintConfig) {
+    let mut items: Vec<EntryRef> = cfg
+        .children
+        .of(dir)
+        .iter()
+        .map(|&id| cfg.tree.entry(id))
+        .filter(|e| e.is_dir() || cfg.render.types.matches(e.kind()))
+        .collect();
+    sort_entries(&mut items, cfg.sort);
+
+    let total = items.len();
+    for (i, item) in items.into_iter().enumerate() {
+        let is_last = i + 1 == total;
+        let branch = if is_last { "└──" } else { "├──" };
+        let icon = kind_icon(&item);
+        let size_str = cfg.render.size_fmt.format(item.size());
+        let counts = if cfg.render.show_counts && item.is_dir() {
+            format!(" {}", format_counts(&item))
+        } else {
+            String::new()
+        };
+
+        let mut fixed = prefix.width() + branch.width() + 1;
+        if cfg.render.show_icon {
+            fixed += icon.width() + 1;
+        }
+        fixed += 1 + size_str.width() + counts.width();
+
+        let name_w = cfg.term_width.saturating_sub(fixed).clamp(4, 120);
+        let mut name_str = display_name(&item);
+        // 不显示图标时，特殊文件在名称后标注类型
+        if !cfg.render.show_icon
+            && !matches!(
+                item.kind(),
+                EntryKind::File | EntryKind::Dir | EntryKind::Symlink
+            )
+        {
+            name_str = Cow::Owned(format!("{} [{}]", name_str, item.kind().as_str()));
+        }
+        let name_trunc = truncate_filename(name_str.as_ref(), name_w);
+        let pad = " ".repeat(name_w.saturating_sub(name_trunc.width()));
+
+        let delta = cfg.growth.map_or(0, |g| g.delta(&item));
+        let name_colored = if delta > 0 {
+            name_trunc.yellow().bold()
+        } else if item.is_dir() {
+            name_trunc.blue().bold()
+        } else {
+            name_trunc.white()
+        };
+        let change = cfg
+            .growth
+            .map(|g| growth_label(delta, g, cfg.render.size_fmt))
+            .unwrap_or_default();
+
+        if cfg.render.show_icon {
+            println!(
+                "{}{} {} {}{} {}{}{}",
+                prefix,
+                branch,
+                icon,
+                name_colored,
+                pad,
+                size_str.cyan(),
+                counts.dimmed(),
+                change
+            );
+        } else {
+            println!(
+                "{}{} {}{} {}{}{}",
+                prefix,
+                branch,
+                name_colored,
+                pad,
+                size_str.cyan(),
+                counts.dimmed(),
+                change
+            );
+        }
+
+        if item.is_dir() {
+            let new_prefix = if is_last {
+                format!("{}    ", prefix)
+            } else {
+                format!("{}│   ", prefix)
+            };
+            print_tree_dir(item.id, &new_prefix, cfg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ScanEntry;
+
+    #[test]
+    fn test_output_text_bar_len_no_div0() {
+        // max_size = 0 时 bar_len 应为 0，不 panic
+        let report = DirReport::from_entries(
+            ".".into(),
+            0,
+            vec![
+                ScanEntry {
+                    name: "a".into(),
+                    size: 0,
+                    is_dir: true,
+                    path: "a".into(),
+                    ..Default::default()
+                },
+                ScanEntry {
+                    name: "b".into(),
+                    size: 0,
+                    is_dir: false,
+                    path: "b".into(),
+                    ..Default::default()
+                },
+            ],
+        )
+        .expect("build report");
+        let opts = RenderOptions {
+            show_chart: true,
+            show_counts: true,
+            ..Default::default()
+        };
+        output_text(&report, &opts, None);
+    }
+
+    #[test]
+    fn test_output_text_empty_entries() {
+        // 空目录不 panic，输出"目录为空"
+        let mut report = DirReport::from_entries(".".into(), 0, vec![]).expect("build report");
+        report.incomplete = true;
+        let mut opts = RenderOptions {
+            show_chart: true,
+            ..Default::default()
+        };
+        output_text(&report, &opts, None);
+        opts.show_chart = false;
+        output_text(&report, &opts, None);
+    }
+}
@@ -0,0 +1,803 @@
+//! 扫描结果：ScanEntry、紧凑节点 arena 与 DirReport。
+
+use crate::error::{AppError, AppResult};
+use crate::scan::{DirStats, EntryKind};
+use crate::sort::{SortSpec, sort_entries};
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+// ---- ScanEntry / DirReport ----
+
+/// 一个扫描条目的独立副本（持有完整路径）。
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ScanEntry {
+    /// 文件名（最后一级路径分量）
+    pub name: OsString,
+    /// 字节数；目录为子树合计
+    pub size: u64,
+    /// 是否为目录（symlink 不跟随，视为叶子）
+    pub is_dir: bool,
+    /// 完整路径（以扫描根为前缀）
+    pub path: PathBuf,
+    /// 子树内文件数（目录为递归计数，文件自身为 1）
+    pub file_count: u64,
+    /// 子树内目录数（递归计数，不含自身；文件为 0）
+    pub dir_count: u64,
+    /// 条目自身的修改时间（不跟随 symlink）；无法读取时为 None
+    pub mtime: Option<SystemTime>,
+    /// 该条目是被跟随的符号链接，统计取自链接目标（`--follow-symlinks all`）
+    pub via_link: bool,
+    /// 条目本身是符号链接（即 `kind == EntryKind::Symlink`）
+    pub is_symlink: bool,
+    /// 条目自身的类型
+    pub kind: EntryKind,
+    /// 符号链接的内容（readlink，原样保存，可能是相对路径）
+    pub target: Option<PathBuf>,
+}
+
+// 自定义序列化/反序列化：将 OsString/PathBuf 在序列化时以 UTF-8 友好的字符串输出（使用 lossy 转换），
+// 反序列化时从字符串恢复为 OsString/PathBuf。
+impl serde::Serialize for ScanEntry {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut s = serializer.serialize_struct("ScanEntry", 10)?;
+        s.serialize_field("name", &self.name.to_string_lossy())?;
+        s.serialize_field("size", &self.size)?;
+        s.serialize_field("is_dir", &self.is_dir)?;
+        s.serialize_field("path", &self.path.to_string_lossy())?;
+        s.serialize_field("file_count", &self.file_count)?;
+        s.serialize_field("dir_count", &self.dir_count)?;
+        s.serialize_field("kind", self.kind.as_str())?;
+        s.serialize_field("is_symlink", &self.is_symlink)?;
+        match &self.target {
+            Some(t) => s.serialize_field("target", &t.to_string_lossy())?,
+            None => s.skip_field("target")?,
+        }
+        // 仅在跟随链接时出现，默认输出与之前保持一致
+        if self.via_link {
+            s.serialize_field("via_link", &true)?;
+        } else {
+            s.skip_field("via_link")?;
+        }
+        s.end()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ScanEntry {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        struct Helper {
+            name: String,
+            size: u64,
+            is_dir: bool,
+            path: String,
+            #[serde(default)]
+            file_count: u64,
+            #[serde(default)]
+            dir_count: u64,
+            #[serde(default)]
+            via_link: bool,
+            #[serde(default)]
+            is_symlink: bool,
+            #[serde(default)]
+            kind: Option<String>,
+            #[serde(default)]
+            target: Option<String>,
+        }
+
+        let h = Helper::deserialize(deserializer)?;
+        Ok(ScanEntry {
+            name: OsString::from(h.name),
+            size: h.size,
+            is_dir: h.is_dir,
+            path: PathBuf::from(h.path),
+            file_count: h.file_count,
+            dir_count: h.dir_count,
+            via_link: h.via_link,
+            is_symlink: h.is_symlink,
+            // 旧版 JSON 没有 kind：按 is_dir / is_symlink 推断
+            kind: h
+                .kind
+                .as_deref()
+                .and_then(EntryKind::from_name)
+                .unwrap_or(if h.is_symlink {
+                    EntryKind::Symlink
+                } else if h.is_dir {
+                    EntryKind::Dir
+                } else {
+                    EntryKind::File
+                }),
+            target: h.target.map(PathBuf::from),
+            ..Default::default()
+        })
+    }
+}
+
+/// 条目字段的统一读取接口：tree 模式逐层构造的 ScanEntry 与报告中的 EntryRef
+/// 共用同一套排序与计数列渲染逻辑。
+pub(crate) trait EntryView {
+    fn name(&self) -> &OsStr;
+    fn size(&self) -> u64;
+    fn is_dir(&self) -> bool;
+    fn file_count(&self) -> u64;
+    fn dir_count(&self) -> u64;
+    fn mtime(&self) -> Option<SystemTime>;
+}
+
+impl EntryView for ScanEntry {
+    fn name(&self) -> &OsStr {
+        &self.name
+    }
+    fn size(&self) -> u64 {
+        self.size
+    }
+    fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+    fn file_count(&self) -> u64 {
+        self.file_count
+    }
+    fn dir_count(&self) -> u64 {
+        self.dir_count
+    }
+    fn mtime(&self) -> Option<SystemTime> {
+        self.mtime
+    }
+}
+
+// ---- 紧凑扫描树（arena） ----
+//
+// 千万级条目的目录树下，每个条目各自持有完整 PathBuf 与名称 OsString 的开销远大于统计数据本身。
+// 扫描结果改存为 arena：节点只记录父节点下标与驻留名称的编号，同名条目
+// （index.js、package.json、__init__.py……）共享一份名称；完整路径仅在输出时沿父链重建。
+
+/// arena 中的节点下标
+pub(crate) type NodeId = u32;
+
+/// 根节点的父下标；子树片段中也用来标记"待挂接到片段外父节点"的顶层节点。
+pub(crate) const NO_PARENT: NodeId = NodeId::MAX;
+
+/// 把 arena 长度换算为下一个编号。编号超出 u32 或撞上 [`NO_PARENT`]
+/// 会让父链错乱，此时返回错误而不是静默回绕。
+fn arena_id(len: usize) -> AppResult<NodeId> {
+    u32::try_from(len)
+        .ok()
+        .filter(|&id| id != NO_PARENT)
+        .ok_or(AppError::TooManyEntries(len))
+}
+
+/// 名称驻留表：相同名称只存一份，节点以编号引用。
+/// 每个名称至少被一个节点引用，名称数不超过节点数，因此节点编号检查通过后名称编号不会溢出。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NameTable {
+    pub(crate) ids: HashMap<Arc<OsStr>, u32>,
+    pub(crate) names: Vec<Arc<OsStr>>,
+}
+
+impl NameTable {
+    pub(crate) fn intern(&mut self, name: &OsStr) -> u32 {
+        match self.ids.get(name) {
+            Some(&id) => id,
+            None => self.insert(Arc::from(name)),
+        }
+    }
+
+    pub(crate) fn intern_shared(&mut self, name: &Arc<OsStr>) -> u32 {
+        match self.ids.get(&**name) {
+            Some(&id) => id,
+            None => self.insert(Arc::clone(name)),
+        }
+    }
+
+    pub(crate) fn insert(&mut self, name: Arc<OsStr>) -> u32 {
+        let id = self.names.len() as u32;
+        self.ids.insert(Arc::clone(&name), id);
+        self.names.push(name);
+        id
+    }
+
+    pub(crate) fn get(&self, id: u32) -> &OsStr {
+        &self.names[id as usize]
+    }
+}
+
+/// 扫描树节点；目录的 size/file_count/dir_count 为子树统计，语义与 ScanEntry 相同。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Node {
+    pub(crate) name_id: u32,
+    pub(crate) parent: NodeId,
+    pub(crate) is_dir: bool,
+    pub(crate) size: u64,
+    pub(crate) file_count: u64,
+    pub(crate) dir_count: u64,
+    pub(crate) mtime: Option<SystemTime>,
+    pub(crate) via_link: bool,
+    pub(crate) kind: EntryKind,
+}
+
+impl Node {
+    pub(crate) fn set_stats(&mut self, st: DirStats) {
+        self.size = st.size;
+        self.file_count = st.files;
+        self.dir_count = st.dirs;
+    }
+}
+
+/// 先序排列的节点 arena：父节点总在子节点之前，且每棵子树占据连续区间。
+/// 下标 0 为根节点（对应 `root` 自身）；并行扫描的子树片段不设根，`root` 为空。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ScanTree {
+    pub(crate) root: PathBuf,
+    pub(crate) nodes: Vec<Node>,
+    pub(crate) names: NameTable,
+    /// 符号链接节点的链接内容；链接通常很少，单独存放而不加大每个节点
+    pub(crate) targets: HashMap<NodeId, PathBuf>,
+}
+
+impl ScanTree {
+    pub(crate) fn new(root: &Path, node: Node) -> Self {
+        let mut tree = ScanTree {
+            root: root.to_path_buf(),
+            ..Default::default()
+        };
+        let name = root.file_name().unwrap_or(root.as_os_str());
+        let name_id = tree.names.intern(name);
+        tree.nodes.push(Node {
+            name_id,
+            parent: NO_PARENT,
+            ..node
+        });
+        tree
+    }
+
+    pub(crate) fn push(
+        &mut self,
+        parent: NodeId,
+        name: &OsStr,
+        mut node: Node,
+    ) -> AppResult<NodeId> {
+        let id = arena_id(self.nodes.len())?;
+        node.name_id = self.names.intern(name);
+        node.parent = parent;
+        self.nodes.push(node);
+        Ok(id)
+    }
+
+    pub(crate) fn push_entry(&mut self, parent: NodeId, e: &ScanEntry) -> AppResult<NodeId> {
+        let id = self.push(parent, &e.name, Node::from(e))?;
+        self.set_target(id, e.target.clone());
+        Ok(id)
+    }
+
+    pub(crate) fn set_target(&mut self, id: NodeId, target: Option<PathBuf>) {
+        if let Some(t) = target {
+            self.targets.insert(id, t);
+        }
+    }
+
+    pub(crate) fn target(&self, id: NodeId) -> Option<&Path> {
+        self.targets.get(&id).map(PathBuf::as_path)
+    }
+
+    /// 将子树片段整体挂到 `parent` 之下：重映射名称编号并平移父下标。
+    /// 合并后的编号超出范围时返回错误，本树保持不变。
+    pub(crate) fn append(&mut self, part: ScanTree, parent: NodeId) -> AppResult<()> {
+        let offset = arena_id(self.nodes.len())?;
+        // 片段内最大下标平移后也必须是合法编号
+        arena_id(self.nodes.len() + part.nodes.len().saturating_sub(1))?;
+        let remap: Vec<u32> = part
+            .names
+            .names
+            .iter()
+            .map(|n| self.names.intern_shared(n))
+            .collect();
+        self.targets
+            .extend(part.targets.into_iter().map(|(id, t)| (id + offset, t)));
+        self.nodes.reserve(part.nodes.len());
+        for mut node in part.nodes {
+            node.name_id = remap[node.name_id as usize];
+            node.parent = if node.parent == NO_PARENT {
+                parent
+            } else {
+                node.parent + offset
+            };
+            self.nodes.push(node);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn node(&self, id: NodeId) -> &Node {
+        &self.nodes[id as usize]
+    }
+
+    pub(crate) fn name(&self, id: NodeId) -> &OsStr {
+        self.names.get(self.node(id).name_id)
+    }
+
+    /// 沿父链重建完整路径（根节点即 `root`）。
+    pub(crate) fn path(&self, id: NodeId) -> PathBuf {
+        let mut chain = Vec::new();
+        let mut cur = id;
+        while cur != 0 {
+            chain.push(self.name(cur));
+            cur = self.node(cur).parent;
+        }
+        let mut p = self.root.clone();
+        for name in chain.iter().rev() {
+            p.push(name);
+        }
+        p
+    }
+
+    /// 构建子节点索引（tree 渲染按目录逐层遍历时使用）。
+    pub(crate) fn child_index(&self) -> ChildIndex {
+        let n = self.nodes.len();
+        let mut start = vec![0u32; n + 1];
+        for node in self.nodes.iter().filter(|n| n.parent != NO_PARENT) {
+            start[node.parent as usize + 1] += 1;
+        }
+        for i in 0..n {
+            start[i + 1] += start[i];
+        }
+        let mut fill = start.clone();
+        let mut list = vec![0; start[n] as usize];
+        for (id, node) in self.nodes.iter().enumerate() {
+            if node.parent != NO_PARENT {
+                let slot = &mut fill[node.parent as usize];
+                list[*slot as usize] = id as NodeId;
+                *slot += 1;
+            }
+        }
+        ChildIndex { start, list }
+    }
+
+    pub(crate) fn entry(&self, id: NodeId) -> EntryRef<'_> {
+        EntryRef { tree: self, id }
+    }
+
+    /// 除根以外的全部节点按后序排列（子树条目在前、目录自身在后），
+    /// 与逐项收集条目时的输出顺序一致。
+    pub(crate) fn post_order(&self) -> Vec<NodeId> {
+        let mut out = Vec::with_capacity(self.nodes.len().saturating_sub(1));
+        let mut open: Vec<NodeId> = Vec::new();
+        for id in 1..self.nodes.len() as NodeId {
+            let parent = self.node(id).parent;
+            while let Some(&top) = open.last()
+                && top != parent
+            {
+                out.push(top);
+                open.pop();
+            }
+            if self.node(id).is_dir {
+                open.push(id);
+            } else {
+                out.push(id);
+            }
+        }
+        out.extend(open.into_iter().rev());
+        out
+    }
+}
+
+/// 子节点索引（CSR 布局）：`start[id]..start[id + 1]` 为 `list` 中 id 的子节点区间，
+/// 每节点仅多 8 字节，子节点保持扫描（目录读取）顺序。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChildIndex {
+    pub(crate) start: Vec<u32>,
+    pub(crate) list: Vec<NodeId>,
+}
+
+impl ChildIndex {
+    pub(crate) fn of(&self, id: NodeId) -> &[NodeId] {
+        let id = id as usize;
+        &self.list[self.start[id] as usize..self.start[id + 1] as usize]
+    }
+}
+
+impl From<&ScanEntry> for Node {
+    fn from(e: &ScanEntry) -> Self {
+        Node {
+            is_dir: e.is_dir,
+            size: e.size,
+            file_count: e.file_count,
+            dir_count: e.dir_count,
+            mtime: e.mtime,
+            via_link: e.via_link,
+            kind: e.kind,
+            ..Default::default()
+        }
+    }
+}
+
+/// 报告条目的借用视图：统计字段直接读 arena，路径在需要时才重建。
+#[derive(Debug, Clone, Copy)]
+pub struct EntryRef<'a> {
+    pub(crate) tree: &'a ScanTree,
+    pub(crate) id: NodeId,
+}
+
+impl<'a> EntryRef<'a> {
+    pub(crate) fn node(&self) -> &'a Node {
+        self.tree.node(self.id)
+    }
+
+    /// 沿父链重建的完整路径。
+    pub fn path(&self) -> PathBuf {
+        self.tree.path(self.id)
+    }
+
+    /// 文件名。
+    pub fn name(&self) -> &'a OsStr {
+        self.tree.name(self.id)
+    }
+
+    /// 字节数；目录为子树合计。
+    pub fn size(&self) -> u64 {
+        self.node().size
+    }
+
+    /// 是否为目录。
+    pub fn is_dir(&self) -> bool {
+        self.node().is_dir
+    }
+
+    /// 子树内文件数。
+    pub fn file_count(&self) -> u64 {
+        self.node().file_count
+    }
+
+    /// 子树内目录数（不含自身）。
+    pub fn dir_count(&self) -> u64 {
+        self.node().dir_count
+    }
+
+    /// 条目自身的修改时间（不跟随 symlink）；无法读取时为 None。
+    pub fn mtime(&self) -> Option<SystemTime> {
+        self.node().mtime
+    }
+
+    /// 是否为被跟随的符号链接。
+    pub fn via_link(&self) -> bool {
+        self.node().via_link
+    }
+
+    /// 是否为符号链接。
+    pub fn is_symlink(&self) -> bool {
+        self.node().kind == EntryKind::Symlink
+    }
+
+    /// 条目自身的类型。
+    pub fn kind(&self) -> EntryKind {
+        self.node().kind
+    }
+
+    /// 符号链接的内容（非链接或读取失败时为 None）。
+    pub fn target(&self) -> Option<&'a Path> {
+        self.tree.target(self.id)
+    }
+
+    /// 物化为持有完整路径的 [`ScanEntry`]。
+    pub fn to_entry(self) -> ScanEntry {
+        let n = self.node();
+        ScanEntry {
+            name: self.name().to_os_string(),
+            size: n.size,
+            is_dir: n.is_dir,
+            path: self.path(),
+            file_count: n.file_count,
+            dir_count: n.dir_count,
+            mtime: n.mtime,
+            via_link: n.via_link,
+            is_symlink: self.is_symlink(),
+            kind: n.kind,
+            target: self.target().map(Path::to_path_buf),
+        }
+    }
+}
+
+impl EntryView for EntryRef<'_> {
+    fn name(&self) -> &OsStr {
+        EntryRef::name(self)
+    }
+    fn size(&self) -> u64 {
+        EntryRef::size(self)
+    }
+    fn is_dir(&self) -> bool {
+        EntryRef::is_dir(self)
+    }
+    fn file_count(&self) -> u64 {
+        EntryRef::file_count(self)
+    }
+    fn dir_count(&self) -> u64 {
+        EntryRef::dir_count(self)
+    }
+    fn mtime(&self) -> Option<SystemTime> {
+        EntryRef::mtime(self)
+    }
+}
+
+// 逐条物化为 ScanEntry 再序列化，JSON 字段与之前完全一致；临时路径用完即弃。
+impl serde::Serialize for EntryRef<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_entry().serialize(serializer)
+    }
+}
+
+/// 一次扫描的结果。条目存放在紧凑 arena 中，经 [`DirReport::entries`] 以借用视图访问。
+#[derive(Debug, Clone)]
+pub struct DirReport {
+    pub(crate) total_size: u64,
+    pub(crate) path: PathBuf,
+    /// 扫描被中断（Ctrl-C），结果仅为已遍历部分
+    pub(crate) incomplete: bool,
+    /// 扫描结果，根节点对应 `path` 自身
+    pub(crate) tree: ScanTree,
+    /// 作为条目输出的节点，顺序即展示顺序
+    pub(crate) order: Vec<NodeId>,
+    /// 库调用时收集的非致命告警（CLI 直接打印，此处为空）；不参与序列化
+    pub(crate) warnings: Vec<String>,
+}
+
+impl DirReport {
+    /// 扫描根路径。
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 根路径下全部内容的字节数合计。
+    pub fn total_size(&self) -> u64 {
+        self.total_size
+    }
+
+    /// 扫描是否被中断，结果仅为已遍历部分。
+    pub fn incomplete(&self) -> bool {
+        self.incomplete
+    }
+
+    /// 报告条目：非递归为根的直接子项，递归为全部后代（后序，子树条目先于所属目录）。
+    pub fn entries(&self) -> impl ExactSizeIterator<Item = EntryRef<'_>> + Clone {
+        self.order.iter().map(|&id| self.tree.entry(id))
+    }
+
+    /// 扫描过程中的非致命告警（无权限的子目录、读取失败的条目等）。
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
+    /// 只保留满足条件的条目（仅影响列出哪些条目，统计不变）。
+    pub(crate) fn retain<F: Fn(&EntryRef) -> bool>(&mut self, f: F) {
+        let tree = &self.tree;
+        self.order.retain(|&id| f(&tree.entry(id)));
+    }
+
+    pub(crate) fn sort(&mut self, spec: SortSpec) {
+        let mut refs: Vec<EntryRef> = self.entries().collect();
+        sort_entries(&mut refs, spec);
+        let order = refs.iter().map(|e| e.id).collect();
+        self.order = order;
+    }
+
+    /// 由扁平条目列表构造报告（反序列化与测试使用）：按 `path` 下的相对路径重建父子关系，
+    /// 缺失的中间目录补为结构节点（不作为条目输出）。
+    pub(crate) fn from_entries(
+        path: PathBuf,
+        total_size: u64,
+        entries: Vec<ScanEntry>,
+    ) -> AppResult<Self> {
+        let mut tree = ScanTree::new(
+            &path,
+            Node {
+                is_dir: true,
+                kind: EntryKind::Dir,
+                ..Default::default()
+            },
+        );
+        let mut ids: HashMap<PathBuf, NodeId> = HashMap::new();
+        let mut order = Vec::with_capacity(entries.len());
+        for e in &entries {
+            let id = match e.path.strip_prefix(&path) {
+                Ok(rel) if rel.as_os_str().is_empty() => 0,
+                Ok(rel) => {
+                    let mut cur = 0;
+                    let mut acc = PathBuf::new();
+                    for comp in rel.iter() {
+                        acc.push(comp);
+                        cur = match ids.get(&acc) {
+                            Some(&id) => id,
+                            None => {
+                                let dir = Node {
+                                    is_dir: true,
+                                    kind: EntryKind::Dir,
+                                    ..Default::default()
+                                };
+                                let id = tree.push(cur, comp, dir)?;
+                                ids.insert(acc.clone(), id);
+                                id
+                            }
+                        };
+                    }
+                    cur
+                }
+                Err(_) => tree.push(0, &e.name, Node::default())?,
+            };
+            let node = &mut tree.nodes[id as usize];
+            *node = Node {
+                name_id: node.name_id,
+                parent: node.parent,
+                ..Node::from(e)
+            };
+            tree.set_target(id, e.target.clone());
+            order.push(id);
+        }
+        Ok(DirReport {
+            total_size,
+            path,
+            incomplete: false,
+            tree,
+            order,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// 以序列形式输出报告条目，不先收集为 Vec。
+pub(crate) struct ReportEntries<'a>(&'a DirReport);
+
+impl serde::Serialize for ReportEntries<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.0.entries())
+    }
+}
+
+impl serde::Serialize for DirReport {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut s = serializer.serialize_struct("DirReport", 4)?;
+        s.serialize_field("total_size", &self.total_size)?;
+        s.serialize_field("entries", &ReportEntries(self))?;
+        s.serialize_field("path", &self.path.to_string_lossy())?;
+        s.serialize_field("incomplete", &self.incomplete)?;
+        s.end()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DirReport {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        struct Helper {
+            total_size: u64,
+            entries: Vec<ScanEntry>,
+            path: String,
+            #[serde(default)]
+            incomplete: bool,
+        }
+
+        let h = Helper::deserialize(deserializer)?;
+        let mut report = DirReport::from_entries(PathBuf::from(h.path), h.total_size, h.entries)
+            .map_err(serde::de::Error::custom)?;
+        report.incomplete = h.incomplete;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exclude::ExcludeSet;
+    use crate::scan::{ScanHooks, analyze_directory, par_min_entries};
+    use crate::test_util::TempDirGuard;
+    use crate::warnings::WarningTracker;
+    use std::fs;
+
+    #[test]
+    fn test_recursive_report_arena_paths_and_json() {
+        let tmp = TempDirGuard::new("yp_report_arena");
+        for d in ["p1", "p2"] {
+            let dir = tmp.path().join(d).join("src");
+            fs::create_dir_all(&dir).expect("failed to create dirs");
+            fs::write(dir.join("index.js"), b"abc").expect("failed to write index.js");
+        }
+
+        let warnings = WarningTracker::new();
+        let excludes = ExcludeSet {
+            patterns: Vec::new(),
+            has_abs: false,
+        };
+        let root = tmp.path();
+        let report = analyze_directory(root, true, &excludes, &warnings, ScanHooks::default())
+            .expect("analyze_directory should succeed");
+
+        // 同名条目共享驻留名称：根、p1、p2、src、index.js 各一份
+        assert_eq!(report.tree.names.names.len(), 5);
+        // 后序：子树条目先于所属目录
+        let paths: Vec<PathBuf> = report.entries().map(|e| e.path()).collect();
+        let pos = |p: PathBuf| paths.iter().position(|x| *x == p).expect("path missing");
+        let p1 = tmp.path().join("p1");
+        assert!(pos(p1.join("src").join("index.js")) < pos(p1.join("src")));
+        assert!(pos(p1.join("src")) < pos(p1.clone()));
+
+        // JSON 往返后条目路径与统计保持不变
+        let json = serde_json::to_string(&report).expect("serialize report");
+        let back: DirReport = serde_json::from_str(&json).expect("deserialize report");
+        assert_eq!(back.total_size, 6);
+        let a: Vec<ScanEntry> = report.entries().map(|e| e.to_entry()).collect();
+        let b: Vec<ScanEntry> = back.entries().map(|e| e.to_entry()).collect();
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(&b) {
+            assert_eq!((&x.path, x.size, x.is_dir), (&y.path, y.size, y.is_dir));
+            assert_eq!((x.file_count, x.dir_count), (y.file_count, y.dir_count));
+        }
+    }
+
+    #[test]
+    fn test_child_index_after_parallel_merge() {
+        let tmp = TempDirGuard::new("yp_child_index");
+        // 根下条目数超过并行阈值，走 rayon 分片 + 片段合并路径
+        let wide = par_min_entries() + 8;
+        for i in 0..wide {
+            let dir = tmp.path().join(format!("d{}", i));
+            fs::create_dir_all(&dir).expect("failed to create dir");
+            fs::write(dir.join("f"), b"x").expect("failed to write file");
+        }
+
+        let warnings = WarningTracker::new();
+        let excludes = ExcludeSet {
+            patterns: Vec::new(),
+            has_abs: false,
+        };
+        let root = tmp.path();
+        let report = analyze_directory(root, true, &excludes, &warnings, ScanHooks::default())
+            .expect("analyze_directory should succeed");
+
+        let tree = &report.tree;
+        let children = tree.child_index();
+        assert_eq!(children.of(0).len(), wide);
+        for &d in children.of(0) {
+            let sub = children.of(d);
+            assert_eq!(sub.len(), 1, "每个目录下应只有一个文件");
+            assert_eq!(tree.name(sub[0]), "f");
+            assert_eq!(tree.path(sub[0]), tree.path(d).join("f"));
+            assert_eq!(tree.node(d).size, 1);
+        }
+    }
+
+    #[test]
+    fn test_arena_id_rejects_overflow() {
+        assert_eq!(arena_id(0).expect("first id"), 0);
+        assert_eq!(
+            arena_id(NO_PARENT as usize - 1).expect("last id"),
+            NO_PARENT - 1
+        );
+        // 恰好等于 NO_PARENT 或超出 u32 都必须报错
+        assert!(matches!(
+            arena_id(NO_PARENT as usize),
+            Err(AppError::TooManyEntries(_))
+        ));
+        assert!(matches!(
+            arena_id(usize::MAX),
+            Err(AppError::TooManyEntries(_))
+        ));
+    }
+}
@@ -0,0 +1,1059 @@
+//! 目录扫描：顶层条目、子树统计与 recursive 逐项扫描。
+
+use crate::cache::{DirStamp, ScanCache};
+use crate::error::{AppError, AppResult};
+use crate::exclude::{ExcludeSet, absify_for_compare};
+use crate::format::app_error_path;
+use crate::i18n::tr;
+use crate::progress::Progress;
+use crate::report::{DirReport, NO_PARENT, Node, NodeId, ScanEntry, ScanTree};
+use crate::visit::{VisitEntry, Visitor};
+use crate::walk::{EntryMeta, FileKey, MetaNeed, list_dir};
+use crate::warnings::{CTX_BROKEN_LINK, CTX_READ_DIR, WarningTracker, io_message};
+use rayon::prelude::*;
+use std::collections::HashSet;
+use std::ffi::{OsStr, OsString};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+// ---- 并行化阈值 ----
+pub(crate) static PAR_MIN_ENTRIES: OnceLock<usize> = OnceLock::new();
+pub(crate) fn par_min_entries() -> usize {
+    *PAR_MIN_ENTRIES.get_or_init(|| {
+        std::env::var("YP_PAR_MIN_ENTRIES")
+            .ok()
+            .and_then(|s| s.parse::<usize>().ok())
+            .filter(|&v| v >= 1)
+            .unwrap_or(64)
+    })
+}
+
+// ---- ScanContext ----
+//
+// [FIX-MAINT-10] 将 root / root_abs / excludes / warnings 聚合为上下文，
+// 减少函数签名中的重复参数。
+pub(crate) struct ScanContext<'a> {
+    /// 用户指定的根路径（原始，用于相对路径显示等）
+    #[allow(dead_code)]
+    pub(crate) root: &'a Path,
+    /// root 的绝对化形式（预计算，避免热路径重复计算）
+    #[allow(dead_code)]
+    pub(crate) root_abs: PathBuf,
+    pub(crate) excludes: &'a ExcludeSet,
+    pub(crate) warnings: &'a WarningTracker,
+    pub(crate) hooks: ScanHooks<'a>,
+    /// 是否把条目写入 ScanTree；仅做回调遍历时为 false，内存占用与条目数无关
+    pub(crate) keep_tree: bool,
+    /// `--follow-symlinks all` 时本次扫描已列举目录的身份，每个目录列举时登记一次；
+    /// 跟随链接前据此识别循环（指向上级目录）与重复（多个链接指向同一目录）
+    pub(crate) seen_dirs: Mutex<HashSet<FileKey>>,
+    /// 建树失败（条目数超出编号范围）时记录的首个错误；记录后遍历按取消处理、尽快收尾
+    pub(crate) error: Mutex<Option<AppError>>,
+    pub(crate) failed: AtomicBool,
+}
+
+impl<'a> ScanContext<'a> {
+    pub(crate) fn new(
+        root: &'a Path,
+        excludes: &'a ExcludeSet,
+        warnings: &'a WarningTracker,
+    ) -> Self {
+        let root_abs = absify_for_compare(root);
+        Self {
+            root,
+            root_abs,
+            excludes,
+            warnings,
+            hooks: ScanHooks::default(),
+            keep_tree: true,
+            seen_dirs: Mutex::new(HashSet::new()),
+            error: Mutex::new(None),
+            failed: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn with_hooks(mut self, hooks: ScanHooks<'a>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// 进入目录时调用（仅在进度开启时有开销）
+    #[inline]
+    pub(crate) fn enter_dir(&self, path: &Path) {
+        if let Some(p) = self.hooks.progress {
+            p.dir(path);
+        }
+    }
+
+    /// 是否已收到取消请求（Ctrl-C）或已经失败；遍历函数在进入目录与逐项循环时检查
+    #[inline]
+    pub(crate) fn cancelled(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+            || self.hooks.cancel.is_some_and(|c| c.load(Ordering::Relaxed))
+    }
+
+    /// 记录致命错误并停止遍历；只保留第一个
+    pub(crate) fn fail(&self, err: AppError) {
+        let mut slot = self.error.lock().unwrap_or_else(|e| e.into_inner());
+        slot.get_or_insert(err);
+        self.failed.store(true, Ordering::Relaxed);
+    }
+
+    /// 取出 [`ScanContext::fail`] 记录的错误
+    pub(crate) fn take_error(&self) -> Option<AppError> {
+        self.error.lock().unwrap_or_else(|e| e.into_inner()).take()
+    }
+
+    /// 记录 IO 告警，并转发给 visitor（若有）
+    pub(crate) fn warn_io(&self, context: &'static str, path: &Path, err: &dyn std::fmt::Display) {
+        let msg = io_message(context, path, err);
+        if let Some(v) = self.hooks.visitor {
+            v.warning(&msg);
+        }
+        self.warnings.warn_io_text(msg);
+    }
+
+    /// 登记目录身份；已登记过时返回 false
+    pub(crate) fn mark_seen(&self, key: FileKey) -> bool {
+        self.seen_dirs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key)
+    }
+
+    pub(crate) fn is_seen(&self, key: &FileKey) -> bool {
+        self.seen_dirs
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(key)
+    }
+
+    /// 统计到一批叶子条目时调用
+    #[inline]
+    pub(crate) fn count_files(&self, files: u64, bytes: u64) {
+        if let Some(p) = self.hooks.progress
+            && files > 0
+        {
+            p.files(files, bytes);
+        }
+    }
+}
+
+/// 扫描期间的可选挂钩与遍历选项，随 ScanContext 传递到所有 rayon worker。
+#[derive(Clone, Copy, Default)]
+pub(crate) struct ScanHooks<'a> {
+    pub(crate) progress: Option<&'a Progress>,
+    pub(crate) cancel: Option<&'a AtomicBool>,
+    pub(crate) cache: Option<&'a ScanCache>,
+    /// recursive 遍历中的逐项回调（库 API `Scanner::visit`）
+    pub(crate) visitor: Option<&'a dyn Visitor>,
+    /// 符号链接跟随策略（默认不跟随）
+    pub(crate) follow: FollowSymlinks,
+}
+
+// ---- 符号链接跟随 ----
+//
+// 默认一律 nofollow：symlink 是大小为链接自身长度的叶子。
+// CmdLine 只解析扫描根自身（类似 du -H），All 跟随遍历中遇到的每个链接（类似 du -L）。
+// 跟随到目录时沿当前路径逐级比较 (dev, ino)，目标是自身祖先即为循环，不再进入。
+
+/// 符号链接跟随策略。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FollowSymlinks {
+    /// 不跟随任何链接（默认）
+    #[default]
+    Never,
+    /// 只跟随扫描根自身（命令行给出的路径）
+    CmdLine,
+    /// 跟随遍历中遇到的全部链接，带循环检测
+    All,
+}
+
+impl FollowSymlinks {
+    pub(crate) fn from_arg(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "never" => Some(Self::Never),
+            "cmdline" => Some(Self::CmdLine),
+            "all" => Some(Self::All),
+            _ => None,
+        }
+    }
+}
+
+// ---- 文件类型辅助 ----
+
+/// 条目自身的类型（lstat 语义：被跟随的链接仍为 `Symlink`）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EntryKind {
+    /// 普通文件
+    #[default]
+    File,
+    /// 目录
+    Dir,
+    /// 符号链接
+    Symlink,
+    /// Unix 域套接字
+    Socket,
+    /// 命名管道
+    Fifo,
+    /// 字符设备
+    CharDevice,
+    /// 块设备
+    BlockDevice,
+    /// 平台特有的其他类型
+    Other,
+}
+
+impl EntryKind {
+    /// JSON 中的 `kind` 取值。
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::File => "file",
+            Self::Dir => "dir",
+            Self::Symlink => "symlink",
+            Self::Socket => "socket",
+            Self::Fifo => "fifo",
+            Self::CharDevice => "char",
+            Self::BlockDevice => "block",
+            Self::Other => "other",
+        }
+    }
+
+    pub(crate) fn from_name(s: &str) -> Option<Self> {
+        [
+            Self::File,
+            Self::Dir,
+            Self::Symlink,
+            Self::Socket,
+            Self::Fifo,
+            Self::CharDevice,
+            Self::BlockDevice,
+            Self::Other,
+        ]
+        .into_iter()
+        .find(|k| k.as_str() == s)
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn from_file_type(ft: fs::FileType) -> Self {
+        use std::os::unix::fs::FileTypeExt;
+        if ft.is_dir() {
+            Self::Dir
+        } else if ft.is_file() {
+            Self::File
+        } else if ft.is_symlink() {
+            Self::Symlink
+        } else if ft.is_socket() {
+            Self::Socket
+        } else if ft.is_fifo() {
+            Self::Fifo
+        } else if ft.is_char_device() {
+            Self::CharDevice
+        } else if ft.is_block_device() {
+            Self::BlockDevice
+        } else {
+            Self::Other
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn from_file_type(ft: fs::FileType) -> Self {
+        if ft.is_dir() {
+            Self::Dir
+        } else if ft.is_file() {
+            Self::File
+        } else if ft.is_symlink() {
+            Self::Symlink
+        } else {
+            Self::Other
+        }
+    }
+
+    /// `st_mode & S_IFMT`（fast_walk 由 d_type 或 statx 得到）。
+    #[cfg(unix)]
+    pub(crate) fn from_mode(fmt: u32) -> Self {
+        match fmt as libc::mode_t {
+            libc::S_IFDIR => Self::Dir,
+            libc::S_IFREG => Self::File,
+            libc::S_IFLNK => Self::Symlink,
+            libc::S_IFSOCK => Self::Socket,
+            libc::S_IFIFO => Self::Fifo,
+            libc::S_IFCHR => Self::CharDevice,
+            libc::S_IFBLK => Self::BlockDevice,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// 统一"叶子"语义（不跟随 symlink）：
+/// - symlink 或 file → Some(len)
+/// - directory       → None（需递归）
+/// - 其他（socket/fifo/device）→ Some(0)（特殊文件不计入大小）
+pub(crate) fn meta_leaf_size_nofollow(meta: &fs::Metadata) -> Option<u64> {
+    let ft = meta.file_type();
+    if ft.is_symlink() || meta.is_file() {
+        Some(meta.len())
+    } else if meta.is_dir() {
+        None
+    } else {
+        Some(0)
+    }
+}
+
+// ---- DirStats ----
+
+/// 子树聚合统计（不跟随 symlink）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[non_exhaustive]
+pub struct DirStats {
+    /// 叶子文件大小之和
+    pub size: u64,
+    /// 叶子文件数（含 symlink 与特殊文件）
+    pub files: u64,
+    /// 子目录数（不含自身）
+    pub dirs: u64,
+}
+
+impl DirStats {
+    pub(crate) fn leaf(size: u64) -> Self {
+        Self {
+            size,
+            files: 1,
+            dirs: 0,
+        }
+    }
+
+    pub(crate) fn add(&mut self, other: DirStats) {
+        self.size += other.size;
+        self.files += other.files;
+        self.dirs += other.dirs;
+    }
+
+    /// 累加一个子目录：其子树统计 + 该目录自身。
+    pub(crate) fn add_dir(&mut self, sub: DirStats) {
+        self.add(sub);
+        self.dirs += 1;
+    }
+}
+
+// ---- process_dir_entry ----
+
+/// 非 recursive 模式的顶层条目（元数据已由 list_dir 取得），目录交给 dir_stats_parallel。
+pub(crate) fn process_dir_entry(
+    dir: &Path,
+    name: OsString,
+    meta: EntryMeta,
+    ctx: &ScanContext,
+) -> Option<ScanEntry> {
+    if ctx.cancelled() {
+        return None;
+    }
+    let p = dir.join(&name);
+    let target = link_target(&p, &meta);
+
+    let stats = match meta.leaf_size {
+        Some(sz) => {
+            ctx.count_files(1, sz);
+            if let Some(v) = ctx.hooks.visitor {
+                v.entry(&VisitEntry {
+                    path: &p,
+                    name: &name,
+                    size: sz,
+                    mtime: meta.mtime,
+                    via_link: meta.via_link,
+                    is_symlink: meta.is_symlink(),
+                    kind: meta.kind,
+                    target: target.as_deref(),
+                });
+            }
+            DirStats::leaf(sz)
+        }
+        // 有 visitor 时逐项回调，子树走 recursive 遍历（不建树）
+        None if ctx.hooks.visitor.is_some() => {
+            scan_dir_recursive(&p, NO_PARENT, &mut ScanTree::default(), ctx)
+        }
+        None => dir_stats_parallel(&p, meta.stamp, ctx, 1),
+    };
+
+    Some(ScanEntry {
+        name,
+        size: stats.size,
+        is_dir: meta.leaf_size.is_none(),
+        path: p,
+        file_count: stats.files,
+        dir_count: stats.dirs,
+        mtime: meta.mtime,
+        via_link: meta.via_link,
+        is_symlink: meta.is_symlink(),
+        kind: meta.kind,
+        target,
+    })
+}
+
+/// 符号链接条目的链接内容；读取失败时为 None（条目本身仍照常统计）。
+pub(crate) fn link_target(path: &Path, meta: &EntryMeta) -> Option<PathBuf> {
+    if meta.is_symlink() {
+        fs::read_link(path).ok()
+    } else {
+        None
+    }
+}
+
+// ---- analyze_directory ----
+//
+// [FIX-BUG-2] 两种模式统一：total_size = 所有叶子文件大小之和。
+// 非 recursive 模式下，目录条目的 size 字段表示其子树大小（供排序/显示），
+// 但 total_size 不再将其累加（避免重复计算）。
+//
+// 挂了 visitor 时（预算规则、--record）两种模式都逐项回调，与显示的结果出自同一次遍历。
+// 缓存命中的目录不读取条目，无法回调，此时忽略 hooks.cache。
+pub(crate) fn analyze_directory(
+    root: &Path,
+    recursive: bool,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+    mut hooks: ScanHooks,
+) -> AppResult<DirReport> {
+    if hooks.visitor.is_some() {
+        hooks.cache = None;
+    }
+    let mut ctx = ScanContext::new(root, excludes, warnings).with_hooks(hooks);
+    // 非 recursive 只保留顶层条目，visitor 触发的子树遍历不建树
+    ctx.keep_tree = recursive;
+    let meta = root_metadata(root, &ctx)?;
+
+    // root 是叶子（文件/symlink/特殊文件）：根节点自身即唯一条目
+    if let Some(sz) = meta_leaf_size_nofollow(&meta) {
+        let node = Node {
+            size: sz,
+            file_count: 1,
+            mtime: meta.modified().ok(),
+            kind: EntryKind::from_file_type(meta.file_type()),
+            ..Default::default()
+        };
+        // 若用户对文件使用 --recursive，给出提示
+        if recursive {
+            warnings.warn_msg(tr("指定路径是文件而非目录，--recursive 无效"));
+        }
+        visit_root_leaf(root, &meta, sz, &ctx);
+        let mut tree = ScanTree::new(root, node);
+        tree.set_target(0, link_target(root, &EntryMeta::from_meta(&meta)));
+        return Ok(DirReport {
+            total_size: sz,
+            path: root.to_path_buf(),
+            incomplete: false,
+            tree,
+            order: vec![0],
+            warnings: Vec::new(),
+        });
+    }
+
+    let mut tree = ScanTree::new(
+        root,
+        Node {
+            is_dir: true,
+            kind: EntryKind::Dir,
+            mtime: meta.modified().ok(),
+            ..Default::default()
+        },
+    );
+
+    if recursive {
+        let stats = scan_dir_recursive(root, 0, &mut tree, &ctx);
+        if let Some(e) = ctx.take_error() {
+            return Err(e);
+        }
+        tree.nodes[0].set_stats(stats);
+        // [FIX-BUG-2] total_size 仅统计叶子文件，与非 recursive 语义一致
+        let order = tree.post_order();
+        Ok(DirReport {
+            total_size: stats.size,
+            path: root.to_path_buf(),
+            incomplete: ctx.cancelled(),
+            tree,
+            order,
+            warnings: Vec::new(),
+        })
+    } else {
+        let need = MetaNeed {
+            mtime: true,
+            stamp: ctx.hooks.cache.is_some(),
+        };
+        let items = list_dir(root, need, &ctx).map_err(|e| AppError::ReadDir {
+            path: app_error_path(root),
+            source: e,
+        })?;
+        if let Some(v) = ctx.hooks.visitor {
+            v.enter_dir(root);
+        }
+
+        // 顶层条目始终并行：目录的子树统计在 dir_stats_parallel 内部继续按子目录拆分，
+        // 由 rayon 工作窃取在各目录之间均衡负载（少数几个巨大目录也能用满多核）。
+        let entries: Vec<ScanEntry> = items
+            .into_par_iter()
+            .filter_map(|(name, meta)| process_dir_entry(root, name, meta, &ctx))
+            .collect();
+
+        // 非 recursive：条目仅为根目录下一层，目录 size 是各自子树总和，
+        // 与同层文件大小互不重叠，因此直接累加全部条目可得到正确总大小。
+        let total_size: u64 = entries.iter().map(|e| e.size).sum();
+        if let Some(v) = ctx.hooks.visitor {
+            let mut totals = DirStats::default();
+            for e in &entries {
+                if e.is_dir {
+                    totals.add_dir(DirStats {
+                        size: e.size,
+                        files: e.file_count,
+                        dirs: e.dir_count,
+                    });
+                } else {
+                    totals.add(DirStats::leaf(e.size));
+                }
+            }
+            v.exit_dir(root, totals);
+        }
+        let order = entries
+            .iter()
+            .map(|e| tree.push_entry(0, e))
+            .collect::<AppResult<_>>()?;
+        Ok(DirReport {
+            total_size,
+            path: root.to_path_buf(),
+            incomplete: ctx.cancelled(),
+            tree,
+            order,
+            warnings: Vec::new(),
+        })
+    }
+}
+
+/// 取扫描根自身的元数据，失败即为致命错误。
+/// 启用跟随时根若为链接则取目标的元数据；链接已失效时告警并按链接自身处理。
+pub(crate) fn root_metadata(root: &Path, ctx: &ScanContext) -> AppResult<fs::Metadata> {
+    let meta = fs::symlink_metadata(root);
+    if ctx.hooks.follow != FollowSymlinks::Never
+        && let Ok(m) = &meta
+        && m.file_type().is_symlink()
+    {
+        match fs::metadata(root) {
+            Ok(target) => return Ok(target),
+            Err(e) => ctx.warn_io(CTX_BROKEN_LINK, root, &e),
+        }
+    }
+    meta.map_err(|e| {
+        if e.kind() == io::ErrorKind::NotFound {
+            AppError::PathNotFound(app_error_path(root))
+        } else {
+            AppError::Metadata {
+                path: app_error_path(root),
+                source: e,
+            }
+        }
+    })
+}
+
+// ---- visit_directory ----
+
+/// 只回调、不建树的 recursive 遍历；返回根的子树统计（根为叶子时即其自身）。
+pub(crate) fn visit_directory(
+    root: &Path,
+    excludes: &ExcludeSet,
+    warnings: &WarningTracker,
+    hooks: ScanHooks,
+) -> AppResult<DirStats> {
+    let mut ctx = ScanContext::new(root, excludes, warnings).with_hooks(hooks);
+    ctx.keep_tree = false;
+    let meta = root_metadata(root, &ctx)?;
+
+    if let Some(sz) = meta_leaf_size_nofollow(&meta) {
+        visit_root_leaf(root, &meta, sz, &ctx);
+        return Ok(DirStats::leaf(sz));
+    }
+    Ok(scan_dir_recursive(root, 0, &mut ScanTree::default(), &ctx))
+}
+
+/// 扫描根本身是叶子时，把它作为唯一条目交给 visitor
+fn visit_root_leaf(root: &Path, meta: &fs::Metadata, size: u64, ctx: &ScanContext) {
+    if let Some(v) = ctx.hooks.visitor {
+        let target = link_target(root, &EntryMeta::from_meta(meta));
+        v.entry(&VisitEntry {
+            path: root,
+            name: root.file_name().unwrap_or(root.as_os_str()),
+            size,
+            mtime: meta.modified().ok(),
+            via_link: false,
+            is_symlink: meta.file_type().is_symlink(),
+            kind: EntryKind::from_file_type(meta.file_type()),
+            target: target.as_deref(),
+        });
+    }
+}
+
+// ---- 并行子树统计（仅大小/计数，不收集条目） ----
+
+/// 并行递归的最大深度；更深的子树改用显式栈迭代，
+/// 使 rayon 嵌套 join 的栈占用有上界，且与目录实际深度无关。
+pub(crate) const PAR_WALK_MAX_DEPTH: usize = 8;
+
+/// 已知为目录的子树统计（不再 stat `path` 自身），不跟随 symlink。
+/// 浅层按子目录并行，子目录之间通过 rayon 工作窃取均衡。
+pub(crate) fn dir_stats_parallel(
+    path: &Path,
+    stamp: DirStamp,
+    ctx: &ScanContext,
+    depth: usize,
+) -> DirStats {
+    if depth >= PAR_WALK_MAX_DEPTH {
+        return dir_stats_iterative(path, stamp, ctx);
+    }
+
+    let (mut sum, subdirs) = read_dir_level(path, stamp, ctx);
+    sum.dirs += subdirs.len() as u64;
+    if subdirs.len() < 2 {
+        for (d, st) in &subdirs {
+            sum.add(dir_stats_parallel(d, *st, ctx, depth + 1));
+        }
+    } else {
+        let sub = subdirs
+            .par_iter()
+            .map(|(d, st)| dir_stats_parallel(d, *st, ctx, depth + 1))
+            .reduce(DirStats::default, |mut a, b| {
+                a.add(b);
+                a
+            });
+        sum.add(sub);
+    }
+    sum
+}
+
+/// 显式栈的串行子树统计，用于超过 PAR_WALK_MAX_DEPTH 的深层目录。
+pub(crate) fn dir_stats_iterative(path: &Path, stamp: DirStamp, ctx: &ScanContext) -> DirStats {
+    let mut sum = DirStats::default();
+    let mut stack = vec![(path.to_path_buf(), stamp)];
+    while let Some((dir, st)) = stack.pop() {
+        let (level, subdirs) = read_dir_level(&dir, st, ctx);
+        sum.add(level);
+        sum.dirs += subdirs.len() as u64;
+        stack.extend(subdirs);
+    }
+    sum
+}
+
+/// 读取一层目录：返回本层叶子统计与子目录列表（附带各自的 stamp），每个条目只 stat 一次。
+/// 启用持久缓存时，stamp 命中的目录直接复用缓存的叶子统计与子目录名，跳过 read_dir 与文件 stat。
+pub(crate) fn read_dir_level(
+    path: &Path,
+    stamp: DirStamp,
+    ctx: &ScanContext,
+) -> (DirStats, Vec<(PathBuf, DirStamp)>) {
+    let mut level = DirStats::default();
+    let mut subdirs = Vec::new();
+    if ctx.cancelled() {
+        return (level, subdirs);
+    }
+
+    if let Some(cache) = ctx.hooks.cache
+        && let Some(hit) = cache.reuse_level(path, stamp)
+    {
+        ctx.enter_dir(path);
+        ctx.count_files(hit.0.files, hit.0.size);
+        return hit;
+    }
+
+    // 子树统计只需大小与类型；stamp 仅在启用缓存时需要
+    let need = MetaNeed {
+        mtime: false,
+        stamp: ctx.hooks.cache.is_some(),
+    };
+    let items = match list_dir(path, need, ctx) {
+        Ok(v) => v,
+        Err(e) => {
+            ctx.warn_io(CTX_READ_DIR, path, &e);
+            return (level, subdirs);
+        }
+    };
+
+    for (name, meta) in items {
+        match meta.leaf_size {
+            Some(sz) => level.add(DirStats::leaf(sz)),
+            None => subdirs.push((path.join(name), meta.stamp)),
+        }
+    }
+    ctx.count_files(level.files, level.size);
+
+    // 中断时本层不完整，不写入缓存
+    if let Some(cache) = ctx.hooks.cache
+        && !ctx.cancelled()
+    {
+        cache.record(path, stamp, level, &subdirs);
+    }
+    (level, subdirs)
+}
+
+/// 递归扫描子树，不跟随 symlink；`path` 下的条目以 `parent` 为父节点追加到 `tree`。
+/// 返回本目录子树统计（不含自身）。
+pub(crate) fn scan_dir_recursive(
+    path: &Path,
+    parent: NodeId,
+    tree: &mut ScanTree,
+    ctx: &ScanContext,
+) -> DirStats {
+    if ctx.cancelled() {
+        return DirStats::default();
+    }
+    if let Some(v) = ctx.hooks.visitor {
+        v.enter_dir(path);
+    }
+    let total = scan_children_recursive(path, parent, tree, ctx);
+    if let Some(v) = ctx.hooks.visitor {
+        v.exit_dir(path, total);
+    }
+    total
+}
+
+fn scan_children_recursive(
+    path: &Path,
+    parent: NodeId,
+    tree: &mut ScanTree,
+    ctx: &ScanContext,
+) -> DirStats {
+    let need = MetaNeed {
+        mtime: true,
+        stamp: false,
+    };
+    // list_dir 返回时目录 fd 已关闭，递归深度不受打开文件数限制
+    let children = match list_dir(path, need, ctx) {
+        Ok(v) => v,
+        Err(e) => {
+            ctx.warn_io(CTX_READ_DIR, path, &e);
+            return DirStats::default();
+        }
+    };
+
+    let mut total = DirStats::default();
+    if children.len() < par_min_entries() {
+        for (name, meta) in children {
+            total.add(scan_one_recursive(path, &name, meta, parent, tree, ctx));
+        }
+    } else {
+        // 每个 rayon 分片写入各自的子树片段，最后按原顺序挂到 parent 下
+        let parts: Vec<(DirStats, ScanTree)> = children
+            .into_par_iter()
+            .fold(
+                || (DirStats::default(), ScanTree::default()),
+                |(mut st, mut part), (name, meta)| {
+                    st.add(scan_one_recursive(
+                        path, &name, meta, NO_PARENT, &mut part, ctx,
+                    ));
+                    (st, part)
+                },
+            )
+            .collect();
+        for (st, part) in parts {
+            total.add(st);
+            if let Err(e) = tree.append(part, parent) {
+                ctx.fail(e);
+                break;
+            }
+        }
+    }
+
+    total
+}
+
+/// 将单个条目追加为 `parent` 的子节点（目录继续递归）；返回其对父目录的统计贡献。
+pub(crate) fn scan_one_recursive(
+    dir: &Path,
+    name: &OsStr,
+    meta: EntryMeta,
+    parent: NodeId,
+    tree: &mut ScanTree,
+    ctx: &ScanContext,
+) -> DirStats {
+    if ctx.cancelled() {
+        return DirStats::default();
+    }
+
+    // 只有链接才拼接路径读取链接内容
+    let target = if meta.is_symlink() {
+        link_target(&dir.join(name), &meta)
+    } else {
+        None
+    };
+
+    if let Some(sz) = meta.leaf_size {
+        ctx.count_files(1, sz);
+        if let Some(v) = ctx.hooks.visitor {
+            v.entry(&VisitEntry {
+                path: &dir.join(name),
+                name,
+                size: sz,
+                mtime: meta.mtime,
+                via_link: meta.via_link,
+                is_symlink: meta.is_symlink(),
+                kind: meta.kind,
+                target: target.as_deref(),
+            });
+        }
+        if ctx.keep_tree {
+            let me = Node {
+                size: sz,
+                file_count: 1,
+                mtime: meta.mtime,
+                via_link: meta.via_link,
+                kind: meta.kind,
+                ..Default::default()
+            };
+            match tree.push(parent, name, me) {
+                Ok(id) => tree.set_target(id, target),
+                Err(e) => ctx.fail(e),
+            }
+        }
+        return DirStats::leaf(sz);
+    }
+
+    // directory：先占位，子树扫描完成后回填统计；不建树时子项直接沿用 parent
+    let id = if ctx.keep_tree {
+        let me = Node {
+            is_dir: true,
+            mtime: meta.mtime,
+            via_link: meta.via_link,
+            kind: meta.kind,
+            ..Default::default()
+        };
+        let id = match tree.push(parent, name, me) {
+            Ok(id) => id,
+            Err(e) => {
+                ctx.fail(e);
+                return DirStats::default();
+            }
+        };
+        tree.set_target(id, target);
+        id
+    } else {
+        parent
+    };
+    let sub = scan_dir_recursive(&dir.join(name), id, tree, ctx);
+    if ctx.keep_tree {
+        tree.nodes[id as usize].set_stats(sub);
+    }
+    // 对父目录的贡献：子树统计 + 本目录自身
+    let mut contrib = DirStats::default();
+    contrib.add_dir(sub);
+    contrib
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDirGuard;
+
+    #[test]
+    fn test_non_recursive_total_size_includes_child_dirs() {
+        let tmp = TempDirGuard::new("yp_non_recursive_total_size");
+        let sub = tmp.path().join("sub");
+        fs::create_dir_all(&sub).expect("failed to create sub dir");
+        fs::write(sub.join("a.txt"), b"a").expect("failed to write a.txt");
+        fs::write(sub.join("b.txt"), b"bb").expect("failed to write b.txt");
+
+        let warnings = WarningTracker::new();
+        let excludes = ExcludeSet {
+            patterns: Vec::new(),
+            has_abs: false,
+        };
+
+        let report = analyze_directory(
+            tmp.path(),
+            false,
+            &excludes,
+            &warnings,
+            ScanHooks::default(),
+        )
+        .expect("analyze_directory should succeed");
+
+        assert_eq!(report.total_size, 3, "非递归模式应统计子目录文件大小");
+        assert_eq!(report.entries().len(), 1, "顶层应只有一个子目录条目");
+        let top = report.entries().next().expect("top-level entry");
+        assert!(top.is_dir(), "顶层条目应为目录");
+        assert_eq!(top.size(), 3, "目录条目大小应为子树总和");
+    }
+
+    #[test]
+    fn test_non_recursive_deep_tree_beyond_parallel_depth() {
+        let tmp = TempDirGuard::new("yp_non_recursive_deep");
+        // 链式深目录，超过 PAR_WALK_MAX_DEPTH 后走显式栈迭代；旁路再加一个兄弟目录触发并行分支
+        let mut dir = tmp.path().join("deep");
+        for i in 0..(PAR_WALK_MAX_DEPTH + 4) {
+            fs::create_dir_all(&dir).expect("failed to create deep dir");
+            fs::write(dir.join(format!("f{}", i)), b"ab").expect("failed to write file");
+            fs::create_dir_all(dir.join("side")).expect("failed to create side dir");
+            dir = dir.join("next");
+        }
+
+        let warnings = WarningTracker::new();
+        let excludes = ExcludeSet {
+            patterns: Vec::new(),
+            has_abs: false,
+        };
+        let report = analyze_directory(
+            tmp.path(),
+            false,
+            &excludes,
+            &warnings,
+            ScanHooks::default(),
+        )
+        .expect("analyze_directory should succeed");
+
+        let levels = (PAR_WALK_MAX_DEPTH + 4) as u64;
+        let deep = report.entries().next().expect("entry 'deep' should exist");
+        assert_eq!(deep.size(), 2 * levels);
+        assert_eq!(deep.file_count(), levels);
+        // 每层一个 side；除最外层 deep 自身外每层一个 next
+        assert_eq!(deep.dir_count(), levels + (levels - 1));
+    }
+
+    #[test]
+    fn test_recursive_file_and_dir_counts() {
+        let tmp = TempDirGuard::new("yp_recursive_counts");
+        let deep = tmp.path().join("a").join("b");
+        fs::create_dir_all(&deep).expect("failed to create nested dirs");
+        fs::write(tmp.path().join("a").join("x.txt"), b"x").expect("failed to write x.txt");
+        fs::write(deep.join("y.txt"), b"yy").expect("failed to write y.txt");
+        fs::write(deep.join("z.txt"), b"zzz").expect("failed to write z.txt");
+
+        let warnings = WarningTracker::new();
+        let excludes = ExcludeSet {
+            patterns: Vec::new(),
+            has_abs: false,
+        };
+        let root = tmp.path();
+
+        for recursive in [false, true] {
+            let report =
+                analyze_directory(root, recursive, &excludes, &warnings, ScanHooks::default())
+                    .expect("analyze_directory should succeed");
+            let a = report
+                .entries()
+                .find(|e| e.name() == "a")
+                .expect("entry 'a' should exist");
+            assert_eq!(a.file_count(), 3, "a 子树应有 3 个文件");
+            assert_eq!(a.dir_count(), 1, "a 子树应有 1 个目录（b）");
+            assert_eq!(a.size(), 6);
+        }
+    }
+
+    #[test]
+    fn test_cancelled_scan_is_marked_incomplete() {
+        let tmp = TempDirGuard::new("yp_cancelled_scan");
+        fs::create_dir_all(tmp.path().join("d")).expect("failed to create dir");
+        fs::write(tmp.path().join("d").join("f"), b"1234").expect("failed to write f");
+
+        let warnings = WarningTracker::new();
+        let excludes = ExcludeSet {
+            patterns: Vec::new(),
+            has_abs: false,
+        };
+        let cancel = AtomicBool::new(true);
+        let root = tmp.path();
+        for recursive in [false, true] {
+            let report = analyze_directory(
+                root,
+                recursive,
+                &excludes,
+                &warnings,
+                ScanHooks {
+                    cancel: Some(&cancel),
+                    ..Default::default()
+                },
+            )
+            .expect("analyze_directory should succeed");
+            assert!(report.incomplete, "取消后的报告应标记为不完整");
+            assert_eq!(report.total_size, 0, "已取消的扫描不应继续遍历");
+
+            let json = serde_json::to_value(&report).expect("serialize report");
+            assert_eq!(json["incomplete"], serde_json::Value::Bool(true));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_follow_symlinks_cycles_and_broken_links() {
+        use std::os::unix::fs::symlink;
+        let tmp = TempDirGuard::new("yp_follow_symlinks");
+        let real = tmp.path().join("real");
+        fs::create_dir_all(&real).expect("failed to create real");
+        fs::write(real.join("f"), vec![0u8; 10]).expect("failed to write f");
+        symlink("..", real.join("up")).expect("symlink up");
+        symlink("real", tmp.path().join("again")).expect("symlink again");
+        symlink(".", tmp.path().join("loop")).expect("symlink loop");
+        symlink("nope", tmp.path().join("broken")).expect("symlink broken");
+        // 两个链接指向扫描根之外的同一目录
+        let outside = TempDirGuard::new("yp_follow_symlinks_outside");
+        fs::write(outside.path().join("g"), vec![0u8; 7]).expect("failed to write g");
+        symlink(outside.path(), tmp.path().join("link")).expect("symlink link");
+        symlink(outside.path(), tmp.path().join("link2")).expect("symlink link2");
+
+        let excludes = ExcludeSet {
+            patterns: Vec::new(),
+            has_abs: false,
+        };
+        let hooks = ScanHooks {
+            follow: FollowSymlinks::All,
+            ..Default::default()
+        };
+        let warnings = WarningTracker::new();
+        let report = analyze_directory(tmp.path(), true, &excludes, &warnings, hooks)
+            .expect("analyze_directory should succeed");
+
+        // real/f 只计一次：again 与 real 是同一目录，先到者统计、后到者只计为空目录或链接自身；
+        // up/loop 形成循环、broken 失效，按链接自身长度计；外部目录的 g 只计一次
+        let again = report
+            .entries()
+            .find(|e| e.path() == tmp.path().join("again"))
+            .expect("entry 'again' should exist");
+        let again_len = if again.is_dir() { 0 } else { 4 };
+        assert_eq!(report.total_size, 10 + 2 + again_len + 1 + 4 + 7);
+        assert_eq!(warnings.warning_total(), 5);
+        let links: Vec<_> = ["link", "link2"]
+            .iter()
+            .map(|n| {
+                report
+                    .entries()
+                    .find(|e| e.path() == tmp.path().join(n))
+                    .expect("link entry should exist")
+            })
+            .collect();
+        assert!(links.iter().all(|l| l.is_dir() && l.via_link()));
+        let mut sizes: Vec<_> = links.iter().map(|l| l.size()).collect();
+        sizes.sort();
+        assert_eq!(sizes, [0, 7]);
+        let json = serde_json::to_value(report.entries().find(|e| e.name() == "loop").unwrap())
+            .expect("serialize entry");
+        assert!(
+            json.get("via_link").is_none(),
+            "未跟随的链接不输出 via_link"
+        );
+
+        // cmdline：根自身为链接时跟随，内部链接保持为叶子
+        let hooks = ScanHooks {
+            follow: FollowSymlinks::CmdLine,
+            ..Default::default()
+        };
+        let warnings = WarningTracker::new();
+        let report = analyze_directory(
+            &tmp.path().join("again"),
+            false,
+            &excludes,
+            &warnings,
+            hooks,
+        )
+        .expect("analyze_directory should follow the root link");
+        assert_eq!(report.total_size, 10 + 2);
+        assert!(report.entries().all(|e| !e.is_dir() && !e.via_link()));
+    }
+}
@@ -0,0 +1,222 @@
+//! 公开的扫描入口：[`Scanner`] 构建器。
+
+use crate::error::AppResult;
+use crate::exclude::compile_excludes;
+use crate::report::DirReport;
+use crate::scan::{DirStats, FollowSymlinks, ScanHooks, analyze_directory, visit_directory};
+use crate::visit::Visitor;
+use crate::warnings::WarningTracker;
+use std::path::PathBuf;
+
+// ---- Scanner ----
+//
+// 库调用不打印任何内容：告警收集进 DirReport::warnings，不显示进度，不响应 Ctrl-C，
+// 不读写扫描缓存。与 CLI 共用同一套遍历实现，因此统计语义与 `yp` 命令完全一致。
+
+/// 目录扫描构建器。
+///
+/// ```no_run
+/// let report = yp::Scanner::new("/var/log")
+///     .recursive(true)
+///     .exclude("node_modules")
+///     .threads(4)
+///     .scan()?;
+/// println!("{} 字节", report.total_size());
+/// # Ok::<(), yp::AppError>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    root: PathBuf,
+    excludes: Vec<String>,
+    recursive: bool,
+    follow: FollowSymlinks,
+    threads: Option<usize>,
+}
+
+impl Scanner {
+    /// 以 `root` 为扫描根；默认非递归、不排除任何条目、使用 rayon 全局线程池。
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            excludes: Vec::new(),
+            recursive: false,
+            follow: FollowSymlinks::Never,
+            threads: None,
+        }
+    }
+
+    /// 追加一个排除模式，语义同 `-e`：不含路径分隔符时按名称匹配任意层级，
+    /// 否则按路径匹配（相对路径以当前工作目录为基准）。
+    pub fn exclude(mut self, pattern: impl Into<String>) -> Self {
+        self.excludes.push(pattern.into());
+        self
+    }
+
+    /// 批量追加排除模式。
+    pub fn excludes<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.excludes.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// 递归模式：报告列出全部后代，而不仅是根的直接子项。
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// 符号链接跟随策略，同 `--follow-symlinks`；默认不跟随。
+    pub fn follow_symlinks(mut self, follow: FollowSymlinks) -> Self {
+        self.follow = follow;
+        self
+    }
+
+    /// 在独立的 `n` 线程池中扫描，不影响调用方的 rayon 全局线程池。
+    pub fn threads(mut self, n: usize) -> Self {
+        self.threads = Some(n);
+        self
+    }
+
+    /// 执行扫描。根路径不存在或无法读取时返回错误；其余 IO 问题记入 [`DirReport::warnings`]。
+    pub fn scan(&self) -> AppResult<DirReport> {
+        let warnings = WarningTracker::collecting();
+        let excludes = compile_excludes(self.excludes.clone(), &warnings);
+        let run = || {
+            analyze_directory(
+                &self.root,
+                self.recursive,
+                &excludes,
+                &warnings,
+                ScanHooks {
+                    follow: self.follow,
+                    ..Default::default()
+                },
+            )
+        };
+        let mut report = match self.threads {
+            Some(n) => rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()?
+                .install(run)?,
+            None => run()?,
+        };
+        report.warnings = warnings.take_collected();
+        Ok(report)
+    }
+
+    /// 递归遍历并逐项回调 `visitor`，不构建 [`DirReport`]（忽略 `recursive` 设置）。
+    /// 返回根的子树统计；告警经 [`Visitor::warning`] 报告。
+    pub fn visit<V: Visitor>(&self, visitor: &V) -> AppResult<DirStats> {
+        let warnings = WarningTracker::collecting();
+        let excludes = compile_excludes(self.excludes.clone(), &warnings);
+        for w in warnings.take_collected() {
+            visitor.warning(&w);
+        }
+        let hooks = ScanHooks {
+            visitor: Some(visitor),
+            follow: self.follow,
+            ..Default::default()
+        };
+        let run = || visit_directory(&self.root, &excludes, &warnings, hooks);
+        match self.threads {
+            Some(n) => rayon::ThreadPoolBuilder::new()
+                .num_threads(n)
+                .build()?
+                .install(run),
+            None => run(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+    use crate::test_util::TempDirGuard;
+    use std::fs;
+
+    #[test]
+    fn test_scanner_builder_scan() {
+        let tmp = TempDirGuard::new("yp_scanner");
+        fs::create_dir_all(tmp.path().join("a").join("b")).expect("failed to create dirs");
+        fs::create_dir_all(tmp.path().join("skip")).expect("failed to create dir");
+        fs::write(tmp.path().join("a").join("b").join("f"), b"12345").expect("write f");
+        fs::write(tmp.path().join("skip").join("g"), b"123").expect("write g");
+
+        let report = Scanner::new(tmp.path())
+            .recursive(true)
+            .exclude("skip")
+            .exclude("")
+            .threads(2)
+            .scan()
+            .expect("scan should succeed");
+        assert_eq!(report.total_size(), 5);
+        assert_eq!(report.path(), tmp.path());
+        assert!(!report.incomplete());
+        let names: Vec<_> = report.entries().map(|e| e.name().to_owned()).collect();
+        assert_eq!(names, ["f", "b", "a"]);
+        // 空排除模式产生的参数告警被收集而非打印
+        assert_eq!(report.warnings().len(), 1);
+
+        let missing = Scanner::new(tmp.path().join("missing")).scan();
+        assert!(matches!(missing, Err(AppError::PathNotFound(_))));
+    }
+
+    #[test]
+    fn test_scanner_visit_callbacks() {
+        use crate::visit::VisitEntry;
+        use std::path::Path;
+        use std::sync::Mutex;
+
+        #[derive(Default)]
+        struct Collect {
+            files: Mutex<Vec<(String, u64)>>,
+            exits: Mutex<Vec<(PathBuf, DirStats)>>,
+            enters: Mutex<usize>,
+        }
+        impl Visitor for Collect {
+            fn entry(&self, e: &VisitEntry<'_>) {
+                let name = e.name.to_string_lossy().into_owned();
+                self.files.lock().unwrap().push((name, e.size));
+            }
+            fn enter_dir(&self, _path: &Path) {
+                *self.enters.lock().unwrap() += 1;
+            }
+            fn exit_dir(&self, path: &Path, totals: DirStats) {
+                self.exits
+                    .lock()
+                    .unwrap()
+                    .push((path.to_path_buf(), totals));
+            }
+        }
+
+        let tmp = TempDirGuard::new("yp_scanner_visit");
+        // 宽目录走 rayon 分片路径，回调来自多个 worker
+        let wide = crate::scan::par_min_entries() + 4;
+        for i in 0..wide {
+            fs::write(tmp.path().join(format!("f{}", i)), b"ab").expect("write file");
+        }
+        fs::create_dir_all(tmp.path().join("sub")).expect("create sub");
+        fs::write(tmp.path().join("sub").join("x"), b"12345").expect("write x");
+
+        let v = Collect::default();
+        let root = Scanner::new(tmp.path())
+            .threads(2)
+            .visit(&v)
+            .expect("visit");
+        assert_eq!(root.size, 2 * wide as u64 + 5);
+        assert_eq!((root.files, root.dirs), (wide as u64 + 1, 1));
+        assert_eq!(v.files.lock().unwrap().len(), wide + 1);
+        assert_eq!(*v.enters.lock().unwrap(), 2);
+
+        let exits = v.exits.lock().unwrap();
+        // 子目录先于根退出，根的统计与返回值一致
+        assert_eq!(exits.len(), 2);
+        assert_eq!(exits[0].0, tmp.path().join("sub"));
+        assert_eq!(exits[0].1.size, 5);
+        assert_eq!(exits[1], (tmp.path().to_path_buf(), root));
+    }
+}
@@ -0,0 +1,172 @@
+//! 条目排序（`--sort` / `--reverse`）。
+
+use crate::report::EntryView;
+use std::cmp::Ordering as CmpOrdering;
+use std::ffi::OsStr;
+use std::path::Path;
+
+// ---- 排序 ----
+
+/// 排序键（`--sort`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum SortKey {
+    /// 按大小降序（默认）
+    #[default]
+    Size,
+    /// 按名称自然序升序（file2 在 file10 之前）
+    Name,
+    /// 按子树 inode 数（文件数 + 目录数）降序
+    Count,
+    /// 按修改时间降序（最新在前）
+    Mtime,
+    /// 按扩展名升序，同扩展名按名称自然序
+    Ext,
+    /// 保持目录读取顺序
+    None,
+}
+
+impl SortKey {
+    pub(crate) fn from_arg(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "size" => Some(Self::Size),
+            "name" => Some(Self::Name),
+            "count" => Some(Self::Count),
+            "mtime" => Some(Self::Mtime),
+            "ext" => Some(Self::Ext),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+}
+
+/// 排序配置：排序键 + 是否反转（`--reverse`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct SortSpec {
+    pub(crate) key: SortKey,
+    pub(crate) reverse: bool,
+}
+
+/// 自然序比较：连续数字按数值比较，其余按字符比较；完全相同时回退到原始字节序保证全序。
+pub(crate) fn natural_cmp(a: &OsStr, b: &OsStr) -> CmpOrdering {
+    let sa = a.to_string_lossy();
+    let sb = b.to_string_lossy();
+    let mut ia = sa.chars().peekable();
+    let mut ib = sb.chars().peekable();
+
+    loop {
+        match (ia.peek().copied(), ib.peek().copied()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return CmpOrdering::Less,
+            (Some(_), None) => return CmpOrdering::Greater,
+            (Some(ca), Some(cb)) if ca.is_ascii_digit() && cb.is_ascii_digit() => {
+                let mut da = String::new();
+                while let Some(c) = ia.next_if(|c| c.is_ascii_digit()) {
+                    da.push(c);
+                }
+                let mut db = String::new();
+                while let Some(c) = ib.next_if(|c| c.is_ascii_digit()) {
+                    db.push(c);
+                }
+                // 去掉前导零后先比长度再比字典序，避免大数溢出
+                let ta = da.trim_start_matches('0');
+                let tb = db.trim_start_matches('0');
+                let ord = ta.len().cmp(&tb.len()).then_with(|| ta.cmp(tb));
+                if ord != CmpOrdering::Equal {
+                    return ord;
+                }
+            }
+            (Some(ca), Some(cb)) => {
+                if ca != cb {
+                    return ca.cmp(&cb);
+                }
+                ia.next();
+                ib.next();
+            }
+        }
+    }
+}
+
+pub(crate) fn extension_key(name: &OsStr) -> String {
+    Path::new(name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+pub(crate) fn compare_entries<T: EntryView>(a: &T, b: &T, key: SortKey) -> CmpOrdering {
+    let by_name = || natural_cmp(a.name(), b.name());
+    match key {
+        SortKey::Size => b.size().cmp(&a.size()).then_with(by_name),
+        SortKey::Name => by_name(),
+        SortKey::Count => (b.file_count() + b.dir_count())
+            .cmp(&(a.file_count() + a.dir_count()))
+            .then_with(by_name),
+        SortKey::Mtime => b.mtime().cmp(&a.mtime()).then_with(by_name),
+        SortKey::Ext => extension_key(a.name())
+            .cmp(&extension_key(b.name()))
+            .then_with(by_name),
+        SortKey::None => CmpOrdering::Equal,
+    }
+}
+
+/// report/tree/JSON 共用的排序入口。
+pub(crate) fn sort_entries<T: EntryView>(entries: &mut [T], spec: SortSpec) {
+    if spec.key == SortKey::None {
+        if spec.reverse {
+            entries.reverse();
+        }
+        return;
+    }
+    if spec.reverse {
+        entries.sort_by(|a, b| compare_entries(a, b, spec.key).reverse());
+    } else {
+        entries.sort_by(|a, b| compare_entries(a, b, spec.key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ScanEntry;
+
+    #[test]
+    fn test_natural_cmp_orders_numbers_by_value() {
+        let mut names = vec!["file10", "file2", "file1", "File3", "file02"];
+        names.sort_by(|a, b| natural_cmp(OsStr::new(a), OsStr::new(b)));
+        assert_eq!(names, vec!["File3", "file1", "file02", "file2", "file10"]);
+    }
+
+    #[test]
+    fn test_sort_entries_keys_and_reverse() {
+        let mk = |name: &str, size: u64, files: u64| ScanEntry {
+            name: name.into(),
+            size,
+            path: name.into(),
+            file_count: files,
+            ..Default::default()
+        };
+        let mut entries = vec![mk("b.txt", 10, 1), mk("a.rs", 30, 5), mk("c.md", 20, 9)];
+        let names = |v: &[ScanEntry]| -> Vec<String> {
+            v.iter()
+                .map(|e| e.name.to_string_lossy().into_owned())
+                .collect()
+        };
+
+        sort_entries(&mut entries, SortSpec::default());
+        assert_eq!(names(&entries), vec!["a.rs", "c.md", "b.txt"]);
+
+        let by_count = SortSpec {
+            key: SortKey::Count,
+            reverse: false,
+        };
+        sort_entries(&mut entries, by_count);
+        assert_eq!(names(&entries), vec!["c.md", "a.rs", "b.txt"]);
+
+        let by_ext_rev = SortSpec {
+            key: SortKey::Ext,
+            reverse: true,
+        };
+        sort_entries(&mut entries, by_ext_rev);
+        assert_eq!(names(&entries), vec!["b.txt", "a.rs", "c.md"]);
+    }
+}
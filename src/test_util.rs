@@ -0,0 +1,92 @@
+//! 测试辅助：自动清理的临时目录，以及各功能模块共用的扫描夹具。
+
+use crate::exclude::{ExcludeSet, compile_excludes};
+use crate::warnings::WarningTracker;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub(crate) struct TempDirGuard {
+    path: PathBuf,
+}
+
+impl TempDirGuard {
+    pub(crate) fn new(prefix: &str) -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let pid = std::process::id();
+        let path = std::env::temp_dir().join(format!("{}_{}_{}", prefix, pid, nanos));
+        fs::create_dir_all(&path).expect("failed to create temp dir");
+        Self { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDirGuard {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+// ---- Fixture ----
+//
+// 功能模块（--broken-links、--duplicates……）的测试都是"建一棵小目录树 → 扫描 → 断言"，
+// 夹具统一提供目录树、排除规则与告警计数，测试只写与功能相关的部分。
+
+pub(crate) struct Fixture {
+    tmp: TempDirGuard,
+    pub(crate) excludes: ExcludeSet,
+    /// 收集模式：告警不打印，可用 take_collected 检查
+    pub(crate) warnings: WarningTracker,
+}
+
+impl Fixture {
+    pub(crate) fn new(prefix: &str) -> Self {
+        Self::with_excludes(prefix, &[])
+    }
+
+    /// `excludes` 按 `-e` 参数的写法编译
+    pub(crate) fn with_excludes(prefix: &str, excludes: &[&str]) -> Self {
+        let warnings = WarningTracker::collecting();
+        let raw = excludes.iter().map(|e| e.to_string()).collect();
+        let excludes = compile_excludes(raw, &warnings);
+        Self {
+            tmp: TempDirGuard::new(prefix),
+            excludes,
+            warnings,
+        }
+    }
+
+    pub(crate) fn root(&self) -> &Path {
+        self.tmp.path()
+    }
+
+    /// `rel` 以 `/` 分隔，转换为本平台路径
+    pub(crate) fn path(&self, rel: &str) -> PathBuf {
+        let mut p = self.root().to_path_buf();
+        p.extend(rel.split('/').filter(|s| !s.is_empty()));
+        p
+    }
+
+    /// 创建目录（含中间目录），返回完整路径
+    pub(crate) fn dir(&self, rel: &str) -> PathBuf {
+        let p = self.path(rel);
+        fs::create_dir_all(&p).expect("failed to create fixture dir");
+        p
+    }
+
+    /// 写入文件（自动创建父目录），返回完整路径
+    pub(crate) fn file(&self, rel: &str, data: impl AsRef<[u8]>) -> PathBuf {
+        let p = self.path(rel);
+        if let Some(parent) = p.parent() {
+            fs::create_dir_all(parent).expect("failed to create fixture dir");
+        }
+        fs::write(&p, data).expect("failed to write fixture file");
+        p
+    }
+}
@@ -0,0 +1,139 @@
+//! 工作线程数与低优先级模式（`--threads` / `--nice`）。
+
+use crate::i18n::trf;
+use crate::warnings::WarningTracker;
+use std::io;
+
+// ---- 线程数 / 低优先级模式 ----
+//
+// --threads 决定 rayon 全局线程池大小；YP_PAR_MIN_ENTRIES 只决定一个目录的子项
+// 是否交给线程池（少于阈值时在当前线程串行处理），二者相互独立：
+// 线程数限制并发 I/O 的上限，阈值控制任务拆分的粒度。--threads 1 时阈值不再影响并发度。
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ThreadConfig {
+    /// 显式线程数（--threads / YP_THREADS）；None 表示使用 rayon 默认值（CPU 核数）
+    pub(crate) threads: Option<usize>,
+    /// 低影响模式（--nice / YP_NICE）：单线程 + 空闲 I/O 优先级 + 最低 CPU 优先级
+    pub(crate) nice: bool,
+}
+
+impl ThreadConfig {
+    /// 从环境变量读取默认值；非法值给出参数警告后忽略。
+    pub(crate) fn from_env(warnings: &WarningTracker) -> Self {
+        let threads =
+            std::env::var("YP_THREADS")
+                .ok()
+                .and_then(|s| match s.trim().parse::<usize>() {
+                    Ok(n) if n >= 1 => Some(n),
+                    _ => {
+                        warnings.warn_msg(&trf("忽略无效的 YP_THREADS={}（需为正整数）", &[&s]));
+                        None
+                    }
+                });
+        let nice = std::env::var("YP_NICE")
+            .map(|s| matches!(s.trim(), "1" | "true" | "yes" | "on"))
+            .unwrap_or(false);
+        Self { threads, nice }
+    }
+
+    /// nice 模式默认单线程；显式线程数优先。
+    pub(crate) fn effective_threads(&self) -> Option<usize> {
+        self.threads.or(if self.nice { Some(1) } else { None })
+    }
+}
+
+/// 在任何并行工作开始前配置 rayon 全局线程池。
+pub(crate) fn configure_thread_pool(cfg: ThreadConfig, warnings: &WarningTracker) {
+    let threads = cfg.effective_threads();
+    if threads.is_none() && !cfg.nice {
+        return;
+    }
+
+    if cfg.nice {
+        // 主线程也会执行部分串行遍历（阈值以下的目录），同样需要降级
+        if let Err(e) = lower_current_thread_priority() {
+            warnings.warn_msg(&trf("无法降低 I/O/CPU 优先级，仅限制线程数 ({})", &[&e]));
+        }
+    }
+
+    let mut builder = rayon::ThreadPoolBuilder::new();
+    if let Some(n) = threads {
+        builder = builder.num_threads(n);
+    }
+    if cfg.nice {
+        builder = builder.start_handler(|_| {
+            let _ = lower_current_thread_priority();
+        });
+    }
+    if let Err(e) = builder.build_global() {
+        warnings.warn_msg(&trf("无法配置线程池，使用默认设置 ({})", &[&e]));
+    }
+}
+
+/// Linux：I/O 调度类设为 idle，CPU nice 设为 19（均只作用于调用线程）。
+#[cfg(target_os = "linux")]
+pub(crate) fn lower_current_thread_priority() -> io::Result<()> {
+    const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+    // SAFETY: ioprio_set 仅修改调用线程的 I/O 优先级，参数均为常量
+    let rc = unsafe {
+        libc::syscall(
+            libc::SYS_ioprio_set,
+            IOPRIO_WHO_PROCESS,
+            0,
+            IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+        )
+    };
+    if rc == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    set_lowest_cpu_priority()
+}
+
+/// 其他 Unix：没有 ioprio，只降低 CPU 优先级。
+#[cfg(all(unix, not(target_os = "linux")))]
+pub(crate) fn lower_current_thread_priority() -> io::Result<()> {
+    set_lowest_cpu_priority()
+}
+
+#[cfg(unix)]
+pub(crate) fn set_lowest_cpu_priority() -> io::Result<()> {
+    // SAFETY: setpriority 仅修改调用线程/进程的 nice 值
+    let rc = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, 19) };
+    if rc == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn lower_current_thread_priority() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        crate::i18n::tr("当前平台不支持调整 I/O 优先级"),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_config_nice_defaults_to_single_thread() {
+        let nice = ThreadConfig {
+            threads: None,
+            nice: true,
+        };
+        assert_eq!(nice.effective_threads(), Some(1));
+
+        let explicit = ThreadConfig {
+            threads: Some(4),
+            nice: true,
+        };
+        assert_eq!(explicit.effective_threads(), Some(4));
+        assert_eq!(ThreadConfig::default().effective_threads(), None);
+    }
+}
@@ -0,0 +1,87 @@
+//! 遍历回调：[`Visitor`] 在扫描过程中逐项接收条目、目录进出与告警。
+
+use crate::scan::{DirStats, EntryKind};
+use std::ffi::OsStr;
+use std::path::Path;
+use std::time::SystemTime;
+
+// ---- Visitor ----
+//
+// 回调直接在遍历线程上执行：条目数达到并行阈值的目录由 rayon worker 分片处理，
+// 因此同一 visitor 会被多个线程并发调用（故要求 Sync，方法只取 &self）。
+// 同一目录的 enter_dir 总在其全部后代回调之前、exit_dir 总在之后；
+// 兄弟目录之间、以及不同线程之间的回调顺序不作保证。
+
+/// 扫描回调。所有方法都有空的默认实现，按需覆盖即可。
+pub trait Visitor: Sync {
+    /// 每个非目录条目（普通文件、symlink、特殊文件）。目录经由 `enter_dir`/`exit_dir` 报告。
+    fn entry(&self, entry: &VisitEntry<'_>) {
+        let _ = entry;
+    }
+
+    /// 开始读取目录 `path`（含扫描根）。
+    fn enter_dir(&self, path: &Path) {
+        let _ = path;
+    }
+
+    /// 目录 `path` 的子树遍历完毕；`totals` 为子树统计（不含目录自身）。
+    /// 目录无法读取时同样调用，统计为零。
+    fn exit_dir(&self, path: &Path, totals: DirStats) {
+        let _ = (path, totals);
+    }
+
+    /// 非致命告警（无法读取的目录、条目等），正文与 CLI 打印的内容相同。
+    fn warning(&self, message: &str) {
+        let _ = message;
+    }
+}
+
+/// 传给 [`Visitor::entry`] 的条目信息，仅在回调期间有效。
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct VisitEntry<'a> {
+    /// 完整路径
+    pub path: &'a Path,
+    /// 文件名
+    pub name: &'a OsStr,
+    /// 字节数（未跟随的 symlink 为链接自身长度）
+    pub size: u64,
+    /// 修改时间（不跟随 symlink）
+    pub mtime: Option<SystemTime>,
+    /// 经跟随的符号链接到达（大小与时间取自链接目标）
+    pub via_link: bool,
+    /// 条目本身是符号链接
+    pub is_symlink: bool,
+    /// 条目自身的类型
+    pub kind: EntryKind,
+    /// 符号链接的内容（非链接时为 None）
+    pub target: Option<&'a Path>,
+}
+
+/// 把回调依次转发给多个 visitor，使预算规则与 `--record` 共用一次扫描。
+pub(crate) struct VisitorChain<'a>(pub(crate) Vec<&'a dyn Visitor>);
+
+impl VisitorChain<'_> {
+    /// 为空时返回 None，扫描不做逐项回调
+    pub(crate) fn as_visitor(&self) -> Option<&dyn Visitor> {
+        (!self.0.is_empty()).then_some(self as &dyn Visitor)
+    }
+}
+
+impl Visitor for VisitorChain<'_> {
+    fn entry(&self, entry: &VisitEntry<'_>) {
+        self.0.iter().for_each(|v| v.entry(entry));
+    }
+
+    fn enter_dir(&self, path: &Path) {
+        self.0.iter().for_each(|v| v.enter_dir(path));
+    }
+
+    fn exit_dir(&self, path: &Path, totals: DirStats) {
+        self.0.iter().for_each(|v| v.exit_dir(path, totals));
+    }
+
+    fn warning(&self, message: &str) {
+        self.0.iter().for_each(|v| v.warning(message));
+    }
+}
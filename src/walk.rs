@@ -0,0 +1,493 @@
+//! 目录列举后端：便携实现与 Linux d_type/statx 快速路径。
+
+use crate::cache::DirStamp;
+use crate::exclude::should_exclude_child;
+use crate::i18n::tr;
+use crate::scan::{
+    EntryKind, FollowSymlinks, ScanContext, meta_leaf_size_nofollow, par_min_entries,
+};
+use crate::warnings::{CTX_BROKEN_LINK, CTX_DIR_SEEN, CTX_LINK_SEEN, CTX_METADATA, CTX_READ_ENTRY};
+use rayon::prelude::*;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+// ---- 目录列举（便携路径 / Linux 快速路径） ----
+//
+// 三条遍历路径（顶层条目、子树统计、recursive 逐项扫描）统一经 list_dir 取得
+// "条目名 + 最小元数据"，调用方声明需要哪些字段，后端据此尽量少做系统调用。
+
+/// 遍历所需的最小元数据（不跟随 symlink）。默认值表示"目录，未取时间/身份"。
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EntryMeta {
+    /// 叶子（文件/symlink/特殊文件）的大小；目录为 None
+    pub(crate) leaf_size: Option<u64>,
+    /// 条目自身的修改时间；仅在 `MetaNeed::mtime` 时保证填充
+    pub(crate) mtime: Option<SystemTime>,
+    /// 目录的身份 + 修改时间；仅在 `MetaNeed::stamp` 时保证填充
+    pub(crate) stamp: DirStamp,
+    /// 条目自身的类型（list_dir 后端总会填充；跟随链接后仍为 Symlink）
+    pub(crate) kind: EntryKind,
+    /// 已跟随链接：其余字段取自链接目标
+    pub(crate) via_link: bool,
+}
+
+impl Default for EntryMeta {
+    fn default() -> Self {
+        EntryMeta {
+            leaf_size: None,
+            mtime: None,
+            stamp: DirStamp::default(),
+            kind: EntryKind::Dir,
+            via_link: false,
+        }
+    }
+}
+
+impl EntryMeta {
+    pub(crate) fn from_meta(meta: &fs::Metadata) -> Self {
+        let leaf_size = meta_leaf_size_nofollow(meta);
+        EntryMeta {
+            leaf_size,
+            mtime: meta.modified().ok(),
+            stamp: if leaf_size.is_none() {
+                DirStamp::from_meta(meta)
+            } else {
+                DirStamp::default()
+            },
+            kind: EntryKind::from_file_type(meta.file_type()),
+            via_link: false,
+        }
+    }
+
+    pub(crate) fn is_symlink(&self) -> bool {
+        self.kind == EntryKind::Symlink
+    }
+}
+
+/// 调用方需要的元数据字段；类型与叶子大小总是需要。
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MetaNeed {
+    /// 所有条目的 mtime（`--sort mtime` 与 JSON 之外的条目视图）
+    pub(crate) mtime: bool,
+    /// 子目录的 DirStamp（仅持久缓存需要）
+    pub(crate) stamp: bool,
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+pub(crate) static FAST_WALK_UNSUPPORTED: AtomicBool = AtomicBool::new(false);
+
+/// 列出目录（已按排除规则过滤）并取得各条目的最小元数据。
+/// 目录本身无法打开时返回 Err，单个条目的错误记入 warnings 后跳过。
+pub(crate) fn list_dir(
+    path: &Path,
+    need: MetaNeed,
+    ctx: &ScanContext,
+) -> io::Result<Vec<(OsString, EntryMeta)>> {
+    let follow = ctx.hooks.follow == FollowSymlinks::All;
+    // 先登记本目录，其下指向它的链接才能被识别为循环
+    if follow
+        && let Some(key) = file_key(path)
+        && !ctx.mark_seen(key)
+    {
+        ctx.warn_io(CTX_DIR_SEEN, path, &tr("同一目录经多条路径到达"));
+        return Ok(Vec::new());
+    }
+    let mut items = list_dir_backend(path, need, ctx)?;
+    if follow {
+        follow_links(path, &mut items, ctx);
+    }
+    ctx.enter_dir(path);
+    Ok(items)
+}
+
+fn list_dir_backend(
+    path: &Path,
+    need: MetaNeed,
+    ctx: &ScanContext,
+) -> io::Result<Vec<(OsString, EntryMeta)>> {
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    if !FAST_WALK_UNSUPPORTED.load(Ordering::Relaxed) {
+        match fast_walk::list_dir(path, need, ctx)? {
+            Some(items) => return Ok(items),
+            None => FAST_WALK_UNSUPPORTED.store(true, Ordering::Relaxed),
+        }
+    }
+    list_dir_portable(path, need, ctx)
+}
+
+/// `--follow-symlinks all`：把链接条目换成目标的元数据。
+/// 失效链接告警后保留为链接自身；目标目录已登记过（上级目录即循环，或已由其他路径统计）时不跟随。
+/// 同一次列举中指向同一目录的多个链接都会被跟随，由 list_dir 的登记保证只统计一次。
+fn follow_links(dir: &Path, items: &mut [(OsString, EntryMeta)], ctx: &ScanContext) {
+    for (name, meta) in items.iter_mut().filter(|(_, m)| m.is_symlink()) {
+        let p = dir.join(&*name);
+        let target = match fs::metadata(&p) {
+            Ok(m) => m,
+            Err(e) => {
+                ctx.warn_io(CTX_BROKEN_LINK, &p, &e);
+                continue;
+            }
+        };
+        if target.is_dir() && meta_key(&p, &target).is_some_and(|k| ctx.is_seen(&k)) {
+            ctx.warn_io(CTX_LINK_SEEN, &p, &tr("目标为上级目录或已由其他路径统计"));
+            continue;
+        }
+        *meta = EntryMeta {
+            kind: EntryKind::Symlink,
+            via_link: true,
+            ..EntryMeta::from_meta(&target)
+        };
+    }
+}
+
+/// 文件身份：Unix 为 (dev, ino)，其他平台退化为规范化后的绝对路径。
+#[cfg(unix)]
+pub(crate) type FileKey = (u64, u64);
+
+#[cfg(not(unix))]
+pub(crate) type FileKey = std::path::PathBuf;
+
+pub(crate) fn file_key(path: &Path) -> Option<FileKey> {
+    meta_key(path, &fs::metadata(path).ok()?)
+}
+
+/// 由已取得的（跟随链接的）元数据求文件身份，Unix 上不再额外 stat。
+#[cfg(unix)]
+fn meta_key(_path: &Path, meta: &fs::Metadata) -> Option<FileKey> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn meta_key(path: &Path, _meta: &fs::Metadata) -> Option<FileKey> {
+    fs::canonicalize(path).ok()
+}
+
+/// 便携实现：std::fs::read_dir + 逐项 symlink_metadata。
+pub(crate) fn list_dir_portable(
+    path: &Path,
+    need: MetaNeed,
+    ctx: &ScanContext,
+) -> io::Result<Vec<(OsString, EntryMeta)>> {
+    let read_dir = fs::read_dir(path)?;
+    let mut items = Vec::new();
+    for res in read_dir {
+        if ctx.cancelled() {
+            break;
+        }
+        match res {
+            Ok(entry) => {
+                let name = entry.file_name();
+                if !should_exclude_child(path, &name, ctx) {
+                    items.push((entry, name));
+                }
+            }
+            Err(err) => ctx.warn_io(CTX_READ_ENTRY, path, &err),
+        }
+    }
+
+    Ok(stat_entries(items, ctx, |(entry, name)| {
+        // 只需类型时沿用 DirEntry 自带的类型（多数平台来自 d_type，无需额外 stat）
+        if !need.mtime && !need.stamp && entry.file_type().is_ok_and(|t| t.is_dir()) {
+            return Some((name, EntryMeta::default()));
+        }
+        let p = entry.path();
+        match fs::symlink_metadata(&p) {
+            Ok(m) => Some((name, EntryMeta::from_meta(&m))),
+            Err(e) => {
+                ctx.warn_io(CTX_METADATA, &p, &e);
+                None
+            }
+        }
+    }))
+}
+
+/// 对列出的条目逐个取元数据；条目数达到并行阈值时交给 rayon。
+pub(crate) fn stat_entries<T, F>(
+    items: Vec<T>,
+    ctx: &ScanContext,
+    f: F,
+) -> Vec<(OsString, EntryMeta)>
+where
+    T: Send,
+    F: Fn(T) -> Option<(OsString, EntryMeta)> + Sync + Send,
+{
+    let f = |item| if ctx.cancelled() { None } else { f(item) };
+    if items.len() < par_min_entries() {
+        items.into_iter().filter_map(f).collect()
+    } else {
+        items.into_par_iter().filter_map(f).collect()
+    }
+}
+
+// ---- Linux 快速遍历后端 ----
+//
+// 便携路径对每个条目都做一次完整路径的 symlink_metadata（内核逐级解析路径，并取全部字段）。
+// Linux（glibc）下改为：
+// - readdir 的 d_type 已给出类型：只需类型的条目（不启用缓存时的子目录、不需要 mtime 时的特殊文件）完全不 stat；
+// - 其余条目用 statx 相对已打开的目录 fd 查询（openat 语义），不拼接完整路径也不重复解析父路径；
+// - statx 掩码只含调用方需要的字段（SIZE / MTIME / INO），部分文件系统据此少做工作。
+// 内核不支持 statx（< 4.11，ENOSYS）时整个进程退回便携路径。
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+mod fast_walk {
+    use super::{
+        CTX_METADATA, CTX_READ_ENTRY, DirStamp, EntryKind, EntryMeta, FollowSymlinks, MetaNeed,
+        ScanContext, should_exclude_child, stat_entries,
+    };
+    use std::ffi::{CStr, CString, OsStr, OsString};
+    use std::io;
+    use std::os::unix::ffi::{OsStrExt, OsStringExt};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    /// 已打开的目录流；drop 时 closedir（同时关闭底层 fd）。
+    struct RawDir(*mut libc::DIR);
+
+    impl RawDir {
+        /// `nofollow` 时路径末级为链接则打开失败（ELOOP），避免目录在遍历中被替换为链接。
+        fn open(path: &Path, nofollow: bool) -> io::Result<Self> {
+            let c_path = CString::new(path.as_os_str().as_bytes())?;
+            let mut flags = libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC;
+            if nofollow {
+                flags |= libc::O_NOFOLLOW;
+            }
+            // SAFETY: c_path 为 NUL 结尾的合法字符串
+            let fd = unsafe { libc::open(c_path.as_ptr(), flags) };
+            if fd < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            // SAFETY: fd 刚由 open 返回，成功后归 DIR 所有
+            let dir = unsafe { libc::fdopendir(fd) };
+            if dir.is_null() {
+                let err = io::Error::last_os_error();
+                // SAFETY: fdopendir 失败时 fd 仍归调用方所有
+                unsafe { libc::close(fd) };
+                return Err(err);
+            }
+            Ok(RawDir(dir))
+        }
+
+        fn fd(&self) -> libc::c_int {
+            // SAFETY: self.0 在 drop 之前一直有效
+            unsafe { libc::dirfd(self.0) }
+        }
+
+        /// 下一个条目（跳过 `.` 与 `..`）：(名称, d_type)。
+        fn next_entry(&mut self) -> Option<io::Result<(CString, u8)>> {
+            loop {
+                // readdir 以 errno 区分"读完"与"出错"
+                // SAFETY: __errno_location 返回当前线程 errno 的有效指针
+                unsafe { *libc::__errno_location() = 0 };
+                // SAFETY: self.0 为有效的目录流，且同一时刻只被一个线程使用
+                let ent = unsafe { libc::readdir64(self.0) };
+                if ent.is_null() {
+                    let err = io::Error::last_os_error();
+                    return (err.raw_os_error() != Some(0)).then_some(Err(err));
+                }
+                // SAFETY: ent 在下一次 readdir 前有效，d_name 以 NUL 结尾
+                let (name, d_type) =
+                    unsafe { (CStr::from_ptr((*ent).d_name.as_ptr()), (*ent).d_type) };
+                if matches!(name.to_bytes(), b"." | b"..") {
+                    continue;
+                }
+                return Some(Ok((name.to_owned(), d_type)));
+            }
+        }
+    }
+
+    impl Drop for RawDir {
+        fn drop(&mut self) {
+            // SAFETY: self.0 由 fdopendir 返回且只关闭一次
+            unsafe { libc::closedir(self.0) };
+        }
+    }
+
+    fn statx_at(dirfd: libc::c_int, name: &CStr, mask: libc::c_uint) -> io::Result<libc::statx> {
+        let mut buf = std::mem::MaybeUninit::<libc::statx>::uninit();
+        let flags = libc::AT_SYMLINK_NOFOLLOW | libc::AT_NO_AUTOMOUNT;
+        // SAFETY: name 为 NUL 结尾字符串，成功时内核写满 buf
+        let rc = unsafe { libc::statx(dirfd, name.as_ptr(), flags, mask, buf.as_mut_ptr()) };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        // SAFETY: rc == 0 表示 buf 已初始化
+        Ok(unsafe { buf.assume_init() })
+    }
+
+    fn system_time(ts: &libc::statx_timestamp) -> SystemTime {
+        if ts.tv_sec >= 0 {
+            UNIX_EPOCH + Duration::new(ts.tv_sec as u64, ts.tv_nsec)
+        } else {
+            UNIX_EPOCH - Duration::from_secs(ts.tv_sec.unsigned_abs())
+                + Duration::from_nanos(ts.tv_nsec.into())
+        }
+    }
+
+    /// 按 d_type 与调用方需要的字段决定 statx 掩码；掩码为空时不发起系统调用。
+    fn entry_meta(
+        dirfd: libc::c_int,
+        name: &CStr,
+        d_type: u8,
+        need: MetaNeed,
+    ) -> io::Result<EntryMeta> {
+        let mut mask = if need.mtime { libc::STATX_MTIME } else { 0 };
+        match d_type {
+            libc::DT_DIR if need.stamp => mask |= libc::STATX_INO | libc::STATX_MTIME,
+            libc::DT_REG | libc::DT_LNK => mask |= libc::STATX_SIZE,
+            libc::DT_UNKNOWN => {
+                mask |= libc::STATX_TYPE | libc::STATX_SIZE | libc::STATX_INO | libc::STATX_MTIME
+            }
+            _ => {}
+        }
+        if mask == 0 {
+            // 仅需类型：目录或特殊文件（大小按 0 计），d_type 已足够
+            return Ok(EntryMeta {
+                leaf_size: (d_type != libc::DT_DIR).then_some(0),
+                kind: EntryKind::from_mode(u32::from(d_type) << 12),
+                ..Default::default()
+            });
+        }
+
+        let stx = statx_at(dirfd, name, mask)?;
+        let fmt = if d_type == libc::DT_UNKNOWN {
+            u32::from(stx.stx_mode) & libc::S_IFMT
+        } else {
+            u32::from(d_type) << 12
+        };
+        let is_dir = fmt == libc::S_IFDIR;
+        let leaf_size = match fmt {
+            libc::S_IFDIR => None,
+            libc::S_IFREG | libc::S_IFLNK => Some(stx.stx_size),
+            _ => Some(0),
+        };
+        let stamp = if is_dir && need.stamp {
+            DirStamp {
+                dev: libc::makedev(stx.stx_dev_major, stx.stx_dev_minor),
+                ino: stx.stx_ino,
+                mtime_s: stx.stx_mtime.tv_sec,
+                mtime_ns: stx.stx_mtime.tv_nsec,
+            }
+        } else {
+            DirStamp::default()
+        };
+        Ok(EntryMeta {
+            leaf_size,
+            mtime: need.mtime.then(|| system_time(&stx.stx_mtime)),
+            stamp,
+            kind: EntryKind::from_mode(fmt),
+            via_link: false,
+        })
+    }
+
+    /// 返回 `Ok(None)` 表示内核不支持 statx，调用方应改走便携路径。
+    pub(super) fn list_dir(
+        path: &Path,
+        need: MetaNeed,
+        ctx: &ScanContext,
+    ) -> io::Result<Option<Vec<(OsString, EntryMeta)>>> {
+        // 跟随模式下扫描根或被跟随的子目录本身可能是链接
+        let nofollow = ctx.hooks.follow == FollowSymlinks::Never;
+        let mut dir = RawDir::open(path, nofollow)?;
+        let mut names = Vec::new();
+        while let Some(res) = dir.next_entry() {
+            if ctx.cancelled() {
+                break;
+            }
+            match res {
+                Ok((name, d_type)) => {
+                    if !should_exclude_child(path, OsStr::from_bytes(name.to_bytes()), ctx) {
+                        names.push((name, d_type));
+                    }
+                }
+                Err(err) => {
+                    // 目录流出错后无法继续读取，保留已读到的部分
+                    ctx.warn_io(CTX_READ_ENTRY, path, &err);
+                    break;
+                }
+            }
+        }
+
+        let fd = dir.fd();
+        let unsupported = AtomicBool::new(false);
+        let items = stat_entries(names, ctx, |(name, d_type)| {
+            match entry_meta(fd, &name, d_type, need) {
+                Ok(meta) => Some((OsString::from_vec(name.into_bytes()), meta)),
+                Err(e) if e.raw_os_error() == Some(libc::ENOSYS) => {
+                    unsupported.store(true, Ordering::Relaxed);
+                    None
+                }
+                Err(e) => {
+                    let p = path.join(OsStr::from_bytes(name.to_bytes()));
+                    ctx.warn_io(CTX_METADATA, &p, &e);
+                    None
+                }
+            }
+        });
+        // 所有 statx 完成后才关闭目录 fd
+        drop(dir);
+
+        if unsupported.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        Ok(Some(items))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exclude::{ExcludePattern, ExcludeSet};
+    use crate::test_util::TempDirGuard;
+    use crate::warnings::WarningTracker;
+
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    #[test]
+    fn test_fast_walk_matches_portable_listing() {
+        let tmp = TempDirGuard::new("yp_fast_walk");
+        fs::create_dir_all(tmp.path().join("sub")).expect("failed to create sub");
+        fs::write(tmp.path().join("a.bin"), vec![0u8; 1234]).expect("failed to write a.bin");
+        fs::write(tmp.path().join("skip.me"), b"x").expect("failed to write skip.me");
+        std::os::unix::fs::symlink("a.bin", tmp.path().join("link")).expect("symlink");
+
+        let warnings = WarningTracker::new();
+        let excludes = ExcludeSet {
+            patterns: vec![ExcludePattern::Name(OsString::from("skip.me"))],
+            has_abs: false,
+        };
+        let ctx = ScanContext::new(tmp.path(), &excludes, &warnings);
+        let need = MetaNeed {
+            mtime: true,
+            stamp: true,
+        };
+        let summarize = |mut items: Vec<(OsString, EntryMeta)>| {
+            items.sort_by(|a, b| a.0.cmp(&b.0));
+            items
+                .into_iter()
+                .map(|(n, m)| (n, m.leaf_size, m.mtime, m.stamp, m.kind))
+                .collect::<Vec<_>>()
+        };
+
+        let fast = fast_walk::list_dir(tmp.path(), need, &ctx)
+            .expect("fast listing should succeed")
+            .expect("statx should be supported");
+        let portable = list_dir_portable(tmp.path(), need, &ctx).expect("portable listing");
+        let fast = summarize(fast);
+        assert_eq!(fast, summarize(portable));
+        let names: Vec<_> = fast.iter().map(|e| e.0.clone()).collect();
+        assert_eq!(names, ["a.bin", "link", "sub"], "排除项不应出现在列表中");
+        assert_eq!(fast[0].1, Some(1234));
+        assert_eq!(fast[1].4, EntryKind::Symlink);
+        assert_eq!(fast[2].1, None, "子目录没有叶子大小");
+        assert_ne!(
+            fast[2].3,
+            DirStamp::default(),
+            "启用 stamp 时子目录应带身份信息"
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! 非致命告警的计数与输出。
+
+use crate::i18n::{tr, trf, trn};
+use crate::progress::CLEAR_LINE;
+use colored::*;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+pub(crate) const WARN_LIMIT: usize = 20;
+
+// 以下上下文同时是 i18n 的消息 ID，由 warn_io 翻译
+
+pub(crate) const CTX_READ_DIR: &str = "无法读取目录";
+
+pub(crate) const CTX_READ_ENTRY: &str = "无法读取目录项";
+
+pub(crate) const CTX_METADATA: &str = "无法读取元数据";
+
+pub(crate) const CTX_BROKEN_LINK: &str = "符号链接目标不存在";
+
+pub(crate) const CTX_LINK_SEEN: &str = "符号链接指向已统计的目录，未跟随";
+
+pub(crate) const CTX_DIR_SEEN: &str = "目录已经由其他路径统计，不重复计入";
+
+pub(crate) const CTX_READ_FILE: &str = "无法读取文件内容";
+
+pub(crate) const CTX_CACHE_READ: &str = "无法读取扫描缓存";
+
+pub(crate) const CTX_CACHE_WRITE: &str = "无法写入扫描缓存";
+
+pub(crate) const CTX_HISTORY_WRITE: &str = "无法写入扫描历史";
+
+/// IO 告警正文（按当前语言翻译上下文）；CLI 输出与 visitor 回调共用
+pub(crate) fn io_message(
+    context: &'static str,
+    path: &Path,
+    err: &dyn std::fmt::Display,
+) -> String {
+    format!("{}: {} ({})", tr(context), path.display(), err)
+}
+
+// ---- WarningTracker ----
+//
+// [FIX-CONCURRENCY-5] emit 的打印限制策略说明：
+//   fetch_add(Relaxed) 后读取旧值 n；当 n < WARN_LIMIT 时打印。
+//   多线程竞争下，总计数是精确的，但打印次数在极端情况下可能略超
+//   WARN_LIMIT（因 fetch_add 与 eprintln 非原子）。
+//   这是"尽力限制"策略：计数精确，打印近似限制，可接受的权衡。
+//   若需严格限制打印次数，应使用 Mutex<usize>，但会引入锁竞争。
+#[derive(Debug)]
+pub(crate) struct WarningTracker {
+    pub(crate) total: AtomicUsize,
+    pub(crate) io_count: AtomicUsize,
+    pub(crate) param_count: AtomicUsize,
+    /// 进度行是否正在 stderr 上显示；为 true 时打印警告前先清除该行
+    pub(crate) progress_active: AtomicBool,
+    /// 库调用时不写 stderr，改为收集告警正文（不含"警告:"前缀与颜色）
+    pub(crate) collected: Option<Mutex<Vec<String>>>,
+}
+
+impl WarningTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            total: AtomicUsize::new(0),
+            io_count: AtomicUsize::new(0),
+            param_count: AtomicUsize::new(0),
+            progress_active: AtomicBool::new(false),
+            collected: None,
+        }
+    }
+
+    /// 收集模式：告警只计数并保存，不打印，供库 API 随报告返回。
+    pub(crate) fn collecting() -> Self {
+        Self {
+            collected: Some(Mutex::new(Vec::new())),
+            ..Self::new()
+        }
+    }
+
+    /// 取出收集到的告警（非收集模式下为空）。
+    pub(crate) fn take_collected(&self) -> Vec<String> {
+        match &self.collected {
+            Some(m) => std::mem::take(&mut *m.lock().unwrap_or_else(|e| e.into_inner())),
+            None => Vec::new(),
+        }
+    }
+
+    pub(crate) fn emit(&self, msg: String) {
+        // fetch_add 返回旧值；旧值 < WARN_LIMIT 时本条消息可以打印
+        let n = self.total.fetch_add(1, Ordering::Relaxed);
+        if let Some(m) = &self.collected {
+            m.lock().unwrap_or_else(|e| e.into_inner()).push(msg);
+            return;
+        }
+        if n < WARN_LIMIT {
+            if self.progress_active.load(Ordering::Relaxed) {
+                eprint!("{}", CLEAR_LINE);
+            }
+            eprintln!("{} {}", tr("警告:").yellow().bold(), msg);
+            // 当本条消息恰好是第 WARN_LIMIT 条时，打印封顶提示
+            if n + 1 == WARN_LIMIT {
+                eprintln!(
+                    "{} {}",
+                    tr("提示:").yellow().bold(),
+                    trf(
+                        "已达到告警上限（{} 条），后续警告将不再逐条打印。",
+                        &[&WARN_LIMIT]
+                    )
+                );
+            }
+        }
+    }
+
+    pub(crate) fn warn_io(&self, context: &'static str, path: &Path, err: &dyn std::fmt::Display) {
+        self.warn_io_text(io_message(context, path, err));
+    }
+
+    /// 记录已由 [`io_message`] 生成正文的 IO 告警
+    pub(crate) fn warn_io_text(&self, msg: String) {
+        self.io_count.fetch_add(1, Ordering::Relaxed);
+        self.emit(msg);
+    }
+
+    pub(crate) fn warn_msg(&self, msg: &str) {
+        self.param_count.fetch_add(1, Ordering::Relaxed);
+        self.emit(msg.to_string());
+    }
+
+    pub(crate) fn warning_total(&self) -> usize {
+        self.total.load(Ordering::Relaxed)
+    }
+    pub(crate) fn warning_io(&self) -> usize {
+        self.io_count.load(Ordering::Relaxed)
+    }
+    pub(crate) fn warning_param(&self) -> usize {
+        self.param_count.load(Ordering::Relaxed)
+    }
+}
+
+pub(crate) fn print_warning_summary(w: &WarningTracker) {
+    let total = w.warning_total();
+    if total == 0 {
+        return;
+    }
+    let io_n = w.warning_io();
+    let param_n = w.warning_param();
+
+    if param_n > 0 {
+        eprintln!(
+            "{} {}",
+            tr("提示:").yellow().bold(),
+            trn(
+                "本次运行产生 {} 条警告（IO: {}，参数: {}）。部分结果可能偏小或与预期不符。",
+                total as u64,
+                &[&total, &io_n, &param_n]
+            )
+        );
+    } else {
+        eprintln!(
+            "{} {}",
+            tr("提示:").yellow().bold(),
+            trn(
+                "本次运行产生 {} 条 IO 警告，部分结果可能偏小。",
+                total as u64,
+                &[&total]
+            )
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_warning_tracker_counts() {
+        let w = WarningTracker::new();
+        assert_eq!(w.warning_total(), 0);
+        w.warn_msg("test param warning");
+        assert_eq!(w.warning_total(), 1);
+        assert_eq!(w.warning_param(), 1);
+        assert_eq!(w.warning_io(), 0);
+    }
+
+    #[test]
+    fn test_warning_tracker_collecting() {
+        let w = WarningTracker::collecting();
+        w.warn_msg("first");
+        w.warn_io(CTX_READ_DIR, Path::new("/x"), &"denied");
+        assert_eq!(w.warning_total(), 2);
+        // 上下文按进程级语言翻译，只检查与语言无关的部分
+        let got = w.take_collected();
+        assert_eq!(got.len(), 2);
+        assert_eq!(got[0], "first");
+        assert!(got[1].ends_with(": /x (denied)"), "{}", got[1]);
+        assert!(w.take_collected().is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+//! 持续刷新（`--watch`）：定期重新扫描并标出增长的条目。
+
+use crate::error::AppResult;
+use crate::report::{DirReport, EntryRef};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+// ---- 刷新循环 ----
+//
+// 每轮完整重新扫描，而非 inotify 增量：inotify 需要为每个目录注册 watch，
+// 大目录树很快会用尽 max_user_watches，且事件只说明"变了"，目录大小仍要重新统计。
+// 两次扫描的开始时间相隔 interval；扫描本身比 interval 慢时立即开始下一轮。
+// 第一次 Ctrl-C 正常结束循环（中断中的那一轮不再输出），第二次照常直接退出。
+
+/// 清屏并把光标移到左上角
+pub(crate) const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// 睡眠期间检查 Ctrl-C 的间隔
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+
+/// 与上一次刷新相比的大小变化
+#[derive(Debug, Clone)]
+pub(crate) struct Growth {
+    prev: HashMap<PathBuf, u64>,
+    prev_total: u64,
+    /// 两次扫描开始时间之差
+    pub(crate) elapsed: Duration,
+}
+
+impl Growth {
+    /// 以 `report` 作为下一轮比较的基准；沿用已有的 HashMap 分配
+    fn rebase(&mut self, report: &DirReport) {
+        self.prev.clear();
+        self.prev
+            .extend(report.entries().map(|e| (e.path(), e.size())));
+        self.prev_total = report.total_size();
+    }
+
+    /// 条目的字节变化；上次不存在的条目从 0 算起
+    pub(crate) fn delta(&self, entry: &EntryRef) -> i64 {
+        let before = self.prev.get(&entry.path()).copied().unwrap_or(0);
+        entry.size() as i64 - before as i64
+    }
+
+    /// 总大小的字节变化
+    pub(crate) fn total_delta(&self, report: &DirReport) -> i64 {
+        report.total_size() as i64 - self.prev_total as i64
+    }
+}
+
+/// 按 `interval` 反复调用 `scan` 并用 `render` 原位重绘，直到 `cancel` 置位。
+/// 除第一次外，`render` 都会收到与上一次的差异。
+pub(crate) fn watch_loop(
+    interval: Duration,
+    cancel: &AtomicBool,
+    mut scan: impl FnMut() -> AppResult<DirReport>,
+    mut render: impl FnMut(&DirReport, Option<&Growth>),
+) -> AppResult<()> {
+    // 只保留上一轮的 (路径, 大小) 快照，不保留整份报告
+    let mut growth: Option<Growth> = None;
+    let mut last_start: Option<Instant> = None;
+    loop {
+        let started = Instant::now();
+        let report = scan()?;
+        if cancel.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        if let (Some(g), Some(at)) = (growth.as_mut(), last_start) {
+            g.elapsed = started - at;
+        }
+        print!("{}", CLEAR_SCREEN);
+        render(&report, growth.as_ref());
+        let _ = io::stdout().flush();
+        growth
+            .get_or_insert_with(|| Growth {
+                prev: HashMap::new(),
+                prev_total: 0,
+                elapsed: Duration::ZERO,
+            })
+            .rebase(&report);
+        last_start = Some(started);
+
+        while let Some(left) = interval.checked_sub(started.elapsed()) {
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            std::thread::sleep(left.min(CANCEL_POLL));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::ScanEntry;
+
+    fn report(total: u64, sizes: &[(&str, u64)]) -> DirReport {
+        let entries = sizes
+            .iter()
+            .map(|&(name, size)| ScanEntry {
+                name: name.into(),
+                path: name.into(),
+                size,
+                ..Default::default()
+            })
+            .collect();
+        DirReport::from_entries(".".into(), total, entries).expect("build report")
+    }
+
+    #[test]
+    fn test_watch_loop_growth() {
+        let cancel = AtomicBool::new(false);
+        let mut round = 0;
+        let mut seen = Vec::new();
+        watch_loop(
+            Duration::ZERO,
+            &cancel,
+            || {
+                round += 1;
+                Ok(match round {
+                    1 => report(10, &[("a", 4), ("b", 6)]),
+                    _ => report(25, &[("a", 4), ("b", 1), ("c", 20)]),
+                })
+            },
+            |r, g| {
+                let deltas: Vec<_> = match g {
+                    Some(g) => r.entries().map(|e| g.delta(&e)).collect(),
+                    None => Vec::new(),
+                };
+                seen.push((g.map(|g| g.total_delta(r)), deltas));
+                if seen.len() == 2 {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+            },
+        )
+        .expect("watch_loop should succeed");
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0], (None, vec![]));
+        // 新条目 c 从 0 算起，缩小的 b 为负
+        assert_eq!(seen[1], (Some(15), vec![0, -5, 20]));
+    }
+}